@@ -0,0 +1,460 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::Result;
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::window::{Window, WindowBuilder};
+
+use crate::asset::AssetServer;
+use crate::graphics::config::RenderConfig;
+use crate::graphics::render::Render;
+use crate::graphics::sprite::SpriteBatch;
+use crate::input::{ActionMap, GamepadBackend, Input, InputEvent, InputRecorder, InputReplayer, Recording};
+use crate::time::Time;
+
+// O jogo. Todos os métodos têm implementação vazia, basta sobrescrever os necessários.
+//
+// Ordem em cada frame: eventos (`on_event`, já aplicados em `ctx.input`), zero ou mais
+// `fixed_update`, `update` e `render`.
+pub trait App {
+    // Chamado uma vez, com a janela e o renderizador já criados (carregar texturas, fontes...)
+    fn init(&mut self, _ctx: &mut AppContext) -> Result<()> {
+        Ok(())
+    }
+
+    // Lógica por frame; `dt` em segundos (igual a `ctx.time.delta()`)
+    fn update(&mut self, _ctx: &mut AppContext, _dt: f32) {}
+
+    // Lógica com passo fixo (física, rede); chamado quantas vezes couber no tempo passado
+    fn fixed_update(&mut self, _ctx: &mut AppContext, _dt: f32) {}
+
+    // Preenche o batch do frame; formas podem ser desenhadas em `ctx.render.shapes_mut()`.
+    // `ctx.time.alpha()` diz quanto já passou do próximo passo fixo, para interpolar posições.
+    fn render(&mut self, _ctx: &mut AppContext, _batch: &mut SpriteBatch) {}
+
+    // Eventos da janela, antes do tratamento padrão do runner (fechar, redimensionar)
+    fn on_event(&mut self, _ctx: &mut AppContext, _event: &WindowEvent<'_>) {}
+
+    // Chamado uma vez antes do programa terminar
+    fn on_exit(&mut self, _ctx: &mut AppContext) {}
+}
+
+// O que o runner empresta ao jogo a cada chamada
+pub struct AppContext {
+    window: Option<Window>,  // `None` ao reproduzir uma gravação sem janela
+    pub render: Render,
+    pub time: Time,
+    pub input: Input,
+    pub actions: ActionMap,  // Atualizado a partir do `input` antes do `fixed_update`/`update`
+    pub assets: AssetServer,  // Carregamentos concluídos são enviados à GPU no início de cada frame
+    quit: bool,
+}
+
+impl AppContext {
+    pub fn window(&self) -> Option<&Window> {
+        self.window.as_ref()
+    }
+
+    // Encerra o loop depois do frame atual (`on_exit` ainda é chamado)
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    pub fn is_quitting(&self) -> bool {
+        self.quit
+    }
+}
+
+// Cria a janela e o renderizador e roda o loop de eventos chamando o `App`.
+//
+//     AppRunner::new(MyGame::default()).title("Meu jogo").size(1280, 720).run()
+pub struct AppRunner<A: App> {
+    app: A,
+    title: String,
+    size: (u32, u32),
+    render_config: RenderConfig,
+    time: Time,
+    gamepads: Option<Box<dyn GamepadBackend>>,
+    record_path: Option<PathBuf>,
+    asset_root: Option<PathBuf>,
+    hot_reload: bool,
+}
+
+impl<A: App + 'static> AppRunner<A> {
+    pub fn new(app: A) -> Self {
+        Self {
+            app,
+            title: String::from("Razor"),
+            size: (1280, 720),
+            render_config: RenderConfig::default(),
+            time: Time::default(),
+            gamepads: default_gamepad_backend(),
+            record_path: None,
+            asset_root: None,
+            hot_reload: cfg!(debug_assertions),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn render_config(mut self, render_config: RenderConfig) -> Self {
+        self.render_config = render_config;
+        self
+    }
+
+    // Intervalo do `fixed_update` em segundos
+    pub fn fixed_timestep(mut self, seconds: f32) -> Self {
+        self.time.set_fixed_timestep(seconds);
+        self
+    }
+
+    // Troca o backend de gamepads (ou desliga, com `None`)
+    pub fn gamepad_backend(mut self, backend: Option<Box<dyn GamepadBackend>>) -> Self {
+        self.gamepads = backend;
+        self
+    }
+
+    // Pasta de onde o `ctx.assets` carrega os arquivos (padrão: `asset::default_root`)
+    pub fn asset_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.asset_root = Some(root.into());
+        self
+    }
+
+    // Recarrega assets alterados em disco enquanto o jogo roda (padrão: só em builds de debug)
+    pub fn hot_reload(mut self, enabled: bool) -> Self {
+        self.hot_reload = enabled;
+        self
+    }
+
+    // Grava a entrada e o tempo de cada frame; o arquivo é salvo ao fechar ou se o jogo
+    // entrar em pânico (.ron ou JSON). Durante a gravação os assets carregam de forma síncrona
+    // e sem hot reload, como no `run_replay`, para os dois verem os assets nos mesmos frames.
+    pub fn record_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+
+    // Só retorna se a inicialização falhar; ao fechar, o processo termina dentro do loop do winit
+    pub fn run(self) -> Result<()> {
+        let Self { mut app, title, size, render_config, time, mut gamepads, record_path, asset_root, hot_reload } = self;
+
+        // Criar o event loop e a janela
+        let event_loop = EventLoop::new();
+        let window = WindowBuilder::new()
+            .with_title(title)
+            .with_inner_size(winit::dpi::PhysicalSize::new(size.0, size.1))
+            .with_transparent(render_config.transparent)
+            .build(&event_loop)?;
+
+        let render = pollster::block_on(Render::with_config(&window, &render_config))?;
+        let mut ctx = AppContext {
+            window: Some(window),
+            render,
+            time,
+            input: Input::new(),
+            actions: ActionMap::new(),
+            assets: asset_root.map(AssetServer::new).unwrap_or_default(),
+            quit: false,
+        };
+        if record_path.is_some() {
+            ctx.assets.set_synchronous(true);
+        } else if hot_reload {
+            if let Err(e) = ctx.assets.watch() {
+                eprintln!("Asset hot reload disabled: {}", e);
+            }
+            if let Some(dir) = &ctx.render.render_config().shader_dir {
+                if let Err(e) = ctx.assets.watch_path(dir) {
+                    eprintln!("Shader hot reload disabled: {}", e);
+                }
+            }
+        }
+        app.init(&mut ctx)?;
+
+        let mut batch = SpriteBatch::new();
+        let mut gamepad_events = Vec::new();
+        let recorder = record_path.clone().map(|path| {
+            let recorder = Arc::new(Mutex::new(InputRecorder::new(ctx.time.fixed_timestep(), ctx.render.size())));
+            save_recording_on_panic(Arc::clone(&recorder), path);
+            recorder
+        });
+        let window_id = ctx.window.as_ref().map(Window::id);
+
+        event_loop.run(move |event, _, control_flow| {
+            *control_flow = ControlFlow::Poll;
+
+            match event {
+                Event::WindowEvent { event, window_id: id } if Some(id) == window_id => {
+                    ctx.input.handle_window_event(&event);
+                    app.on_event(&mut ctx, &event);
+                    match event {
+                        WindowEvent::CloseRequested => ctx.quit(),
+                        WindowEvent::Resized(physical_size) => ctx.render.resize(physical_size),
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => ctx.render.resize(*new_inner_size),
+                        _ => {}
+                    }
+                }
+                Event::DeviceEvent { event, .. } => {
+                    ctx.input.handle_device_event(&event);
+                }
+                Event::MainEventsCleared => {
+                    if let Some(backend) = &mut gamepads {
+                        backend.poll(&mut gamepad_events);
+                        for event in gamepad_events.drain(..) {
+                            ctx.input.inject(event);
+                            if let InputEvent::GamepadConnected(id) = event {
+                                if let Some(name) = backend.name(id) {
+                                    ctx.input.set_gamepad_name(id, name);
+                                }
+                            }
+                        }
+                    }
+
+                    ctx.time.tick();
+                    simulate_frame(&mut app, &mut ctx, recorder.as_deref());
+
+                    if let Some(window) = &ctx.window {
+                        window.request_redraw();
+                    }
+                }
+                Event::RedrawRequested(id) if Some(id) == window_id => draw_frame(&mut app, &mut ctx, &mut batch),
+                Event::LoopDestroyed => {
+                    app.on_exit(&mut ctx);
+                    if let (Some(recorder), Some(path)) = (&recorder, &record_path) {
+                        let recorder = recorder.lock().unwrap_or_else(PoisonError::into_inner);
+                        save_recording(&recorder, path);
+                    }
+                }
+                _ => {}
+            }
+
+            if ctx.quit {
+                *control_flow = ControlFlow::Exit;
+            }
+        });
+    }
+
+    // Reproduz uma gravação sem janela, frame a frame, com o mesmo passo fixo e os mesmos deltas.
+    // Desenha numa textura offscreen (`ctx.render.read_pixels` funciona) e devolve o `App`
+    // no estado final para inspeção. `on_event` não é chamado: só a entrada é reproduzida.
+    pub fn run_replay(self, recording: Recording) -> Result<A> {
+        let Self { mut app, render_config, mut time, asset_root, .. } = self;
+
+        let (width, height) = recording.size;
+        let render_config = render_config.backends(wgpu::Backends::all());  // Qualquer adaptador serve (CI sem GPU)
+        let render = pollster::block_on(Render::new_headless_with_config(width, height, &render_config))?;
+        time.set_fixed_timestep(recording.fixed_timestep);
+        let mut ctx = AppContext {
+            window: None,
+            render,
+            time,
+            input: Input::new(),
+            actions: ActionMap::new(),
+            assets: asset_root.map(AssetServer::new).unwrap_or_default(),
+            quit: false,
+        };
+        // Carregamentos concluídos sempre no mesmo frame e nada de arquivos alterados em disco:
+        // o resultado não pode depender das threads
+        ctx.assets.set_synchronous(true);
+        app.init(&mut ctx)?;
+        ctx.assets.unwatch();
+
+        let mut batch = SpriteBatch::new();
+        let mut replayer = InputReplayer::new(recording);
+        while !ctx.quit {
+            let Some(frame) = replayer.next_frame(&mut ctx.input) else {
+                break;
+            };
+            // Na janela, o redimensionamento chega antes da lógica do frame
+            if let Some((width, height)) = frame.resize {
+                ctx.render.resize(winit::dpi::PhysicalSize::new(width, height));
+            }
+            ctx.time.advance(frame.delta);
+            simulate_frame(&mut app, &mut ctx, None);
+            draw_frame(&mut app, &mut ctx, &mut batch);
+        }
+
+        app.on_exit(&mut ctx);
+        Ok(app)
+    }
+}
+
+// Lógica de um frame, comum ao loop da janela e à reprodução: assets, ações, passos fixos e `update`.
+// O relógio já deve ter avançado (`Time::tick` ou `Time::advance`).
+fn simulate_frame<A: App>(app: &mut A, ctx: &mut AppContext, recorder: Option<&Mutex<InputRecorder>>) {
+    // Gravado antes da lógica do jogo: se ela entrar em pânico, o frame que causou o problema
+    // já está na gravação
+    if let Some(recorder) = recorder {
+        let mut recorder = recorder.lock().unwrap_or_else(PoisonError::into_inner);
+        recorder.record_frame(ctx.time.raw_delta(), &ctx.input, ctx.render.size());
+    }
+
+    ctx.assets.update(&mut ctx.render);
+    if let Err(e) = ctx.render.reload_changed_shaders(ctx.assets.changed_files()) {
+        eprintln!("{}", e);  // O pipeline anterior continua em uso
+    }
+    ctx.actions.update(&ctx.input);
+    while ctx.time.expend_fixed_step() {
+        let step = ctx.time.fixed_timestep();
+        app.fixed_update(ctx, step);
+    }
+    let dt = ctx.time.delta();
+    app.update(ctx, dt);
+    ctx.input.end_frame();
+}
+
+fn save_recording(recorder: &InputRecorder, path: &Path) {
+    match recorder.recording().save(path) {
+        Ok(()) => eprintln!("Input recording saved to {}", path.display()),
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+// Salva a gravação também num pânico (inclusive com `panic = "abort"`), o caso de reproduzir
+// um bug; o hook anterior continua sendo chamado antes
+fn save_recording_on_panic(recorder: Arc<Mutex<InputRecorder>>, path: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        // A gravação só fica travada durante o `record_frame`; se o pânico foi lá, desiste
+        if let Ok(recorder) = recorder.try_lock() {
+            save_recording(&recorder, &path);
+        }
+    }));
+}
+
+fn draw_frame<A: App>(app: &mut A, ctx: &mut AppContext, batch: &mut SpriteBatch) {
+    batch.clear();
+    app.render(ctx, batch);
+    if let Err(e) = ctx.render.render_batch(batch) {
+        eprintln!("Render error: {}", e);
+        if e.is_fatal() {
+            ctx.quit();
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn default_gamepad_backend() -> Option<Box<dyn GamepadBackend>> {
+    match crate::input::GilrsBackend::new() {
+        Ok(backend) => Some(Box::new(backend)),
+        Err(e) => {
+            eprintln!("Gamepads disabled: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+fn default_gamepad_backend() -> Option<Box<dyn GamepadBackend>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::*;
+    use crate::input::{GamepadButton, GamepadId, Key, MouseButton};
+
+    // O que a lógica do jogo enxerga num frame
+    #[derive(Clone, Debug, PartialEq)]
+    struct FrameState {
+        delta: f32,
+        size: (u32, u32),
+        keys: Vec<(bool, bool, bool)>,  // (pressionada, just_pressed, just_released)
+        left_mouse: (bool, bool, bool),
+        mouse_position: Vec2,
+        mouse_delta: Vec2,
+        mouse_wheel: Vec2,
+        text: String,
+        gamepad_south: bool,
+        fixed_steps: u64,
+    }
+
+    impl FrameState {
+        fn capture(input: &Input, delta: f32, size: (u32, u32), fixed_steps: u64) -> Self {
+            Self {
+                delta,
+                size,
+                keys: [Key::W, Key::Space]
+                    .into_iter()
+                    .map(|key| (input.is_pressed(key), input.just_pressed(key), input.just_released(key)))
+                    .collect(),
+                left_mouse: (
+                    input.is_mouse_pressed(MouseButton::Left),
+                    input.mouse_just_pressed(MouseButton::Left),
+                    input.mouse_just_released(MouseButton::Left),
+                ),
+                mouse_position: input.mouse_position(),
+                mouse_delta: input.mouse_delta(),
+                mouse_wheel: input.mouse_wheel(),
+                text: String::from(input.text()),
+                gamepad_south: input.gamepad_pressed(GamepadId(0), GamepadButton::South),
+                fixed_steps,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Probe {
+        frames: Vec<FrameState>,
+    }
+
+    impl App for Probe {
+        fn update(&mut self, ctx: &mut AppContext, dt: f32) {
+            let state = FrameState::capture(&ctx.input, dt, ctx.render.size(), ctx.time.fixed_step_count());
+            self.frames.push(state);
+        }
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_input_and_deltas() {
+        if pollster::block_on(Render::new_headless(1, 1)).is_err() {
+            eprintln!("Sem adaptador para o teste");
+            return;
+        }
+
+        let frames: Vec<(f32, Vec<InputEvent>, (u32, u32))> = vec![
+            (0.016, vec![InputEvent::KeyPressed(Key::W), InputEvent::CursorMoved([10.0, 20.0])], (64, 48)),
+            (0.021, vec![InputEvent::MousePressed(MouseButton::Left), InputEvent::CursorMoved([14.0, 17.0])], (64, 48)),
+            (0.004, vec![InputEvent::Text('x'), InputEvent::MouseWheel([0.0, -1.0])], (64, 48)),
+            (0.033, vec![InputEvent::KeyReleased(Key::W), InputEvent::KeyPressed(Key::Space)], (80, 60)),
+            (0.017, vec![InputEvent::GamepadPressed(GamepadId(0), GamepadButton::South), InputEvent::FocusLost], (80, 60)),
+            (0.016, Vec::new(), (80, 60)),
+        ];
+
+        // Lado da gravação: o mesmo caminho do `simulate_frame`, com a entrada injetada
+        let fixed_timestep = 1.0 / 60.0;
+        let mut input = Input::new();
+        let mut time = Time::new(fixed_timestep);
+        let mut recorder = InputRecorder::new(fixed_timestep, (64, 48));
+        let mut expected = Vec::new();
+        for (delta, events, size) in frames {
+            for event in events {
+                input.inject(event);
+            }
+            time.advance(delta);
+            recorder.record_frame(time.raw_delta(), &input, size);
+            while time.expend_fixed_step() {}
+            expected.push(FrameState::capture(&input, time.delta(), size, time.fixed_step_count()));
+            input.end_frame();
+        }
+
+        let path = std::env::temp_dir().join(format!("base-app-replay-test-{}.json", std::process::id()));
+        recorder.recording().save(&path).unwrap();
+        let recording = Recording::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let probe = AppRunner::new(Probe::default()).run_replay(recording.unwrap()).unwrap();
+        assert_eq!(probe.frames, expected);
+    }
+}
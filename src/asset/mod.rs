@@ -0,0 +1,620 @@
+// Carregamento de arquivos do jogo (texturas...) em threads de fundo.
+//
+// Os caminhos são relativos à raiz de assets do `AssetServer`, não ao diretório atual:
+// `assets.load::<Texture>("images/razor.png")` devolve na hora um `Handle`, que fica
+// `Loaded` depois que o arquivo foi lido, decodificado e enviado para a GPU.
+//
+// Com `watch` (ligado por padrão pelo `AppRunner` em builds de debug), arquivos alterados em
+// disco são recarregados nos mesmos handles enquanto o jogo roda.
+
+mod watcher;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use anyhow::Result;
+
+use crate::graphics::render::Render;
+use crate::graphics::texture::{Texture, TextureHandle};
+use watcher::FileWatcher;
+
+// Variável de ambiente que sobrescreve a raiz de assets padrão
+pub const ASSET_ROOT_VAR: &str = "ASSET_ROOT";
+
+// Tipo que pode ser carregado pelo `AssetServer`. A leitura e o `decode` rodam numa thread
+// de fundo; o `upload` roda na thread do renderizador, dentro do `AssetServer::update`.
+pub trait Asset: 'static {
+    type Decoded: Send + 'static;  // Resultado do decode (ex.: pixels da imagem)
+    type Loaded: 'static;          // O que fica guardado no servidor (ex.: handle da textura na GPU)
+
+    fn decode(bytes: Vec<u8>, path: &Path) -> Result<Self::Decoded>;
+    fn upload(decoded: Self::Decoded, render: &mut Render, path: &Path) -> Result<Self::Loaded>;
+
+    // Arquivo alterado em disco: atualiza o asset já carregado. O padrão troca pelo novo upload;
+    // tipos com recursos na GPU podem reaproveitá-los para manter handles e bind groups válidos.
+    fn reload(decoded: Self::Decoded, loaded: &mut Self::Loaded, render: &mut Render, path: &Path) -> Result<()> {
+        *loaded = Self::upload(decoded, render, path)?;
+        Ok(())
+    }
+}
+
+impl Asset for Texture {
+    type Decoded = image::RgbaImage;
+    type Loaded = TextureHandle;
+
+    fn decode(bytes: Vec<u8>, path: &Path) -> Result<image::RgbaImage> {
+        let img = image::load_from_memory(&bytes)
+            .map_err(|e| anyhow::anyhow!("Erro ao carregar a imagem {}: {}", path.display(), e))?;
+        Ok(img.to_rgba8())
+    }
+
+    fn upload(decoded: image::RgbaImage, render: &mut Render, path: &Path) -> Result<TextureHandle> {
+        Ok(render.create_texture(&decoded, &path.display().to_string()))
+    }
+
+    // Mesma `TextureHandle`: os sprites que já usam a textura passam a mostrar a nova imagem
+    fn reload(decoded: image::RgbaImage, loaded: &mut TextureHandle, render: &mut Render, path: &Path) -> Result<()> {
+        if !render.replace_texture(*loaded, &decoded) {
+            *loaded = Self::upload(decoded, render, path)?;
+        }
+        Ok(())
+    }
+}
+
+// Código-fonte WGSL (ou qualquer texto UTF-8), para quem monta pipelines a partir de arquivos
+pub struct ShaderSource;
+
+impl Asset for ShaderSource {
+    type Decoded = String;
+    type Loaded = String;
+
+    fn decode(bytes: Vec<u8>, path: &Path) -> Result<String> {
+        String::from_utf8(bytes).map_err(|e| anyhow::anyhow!("{} não é UTF-8: {}", path.display(), e))
+    }
+
+    fn upload(decoded: String, _render: &mut Render, _path: &Path) -> Result<String> {
+        Ok(decoded)
+    }
+}
+
+// Identificador tipado de um asset do `AssetServer`. O mesmo caminho devolve sempre o mesmo handle.
+pub struct Handle<T: Asset> {
+    index: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset> Handle<T> {
+    fn new(index: u32) -> Self {
+        Self { index, marker: PhantomData }
+    }
+}
+
+// Implementados à mão: o derive exigiria `T: Clone`, `T: PartialEq`...
+impl<T: Asset> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Asset> Copy for Handle<T> {}
+
+impl<T: Asset> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T: Asset> Eq for Handle<T> {}
+
+impl<T: Asset> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T: Asset> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle<{}>({})", std::any::type_name::<T>(), self.index)
+    }
+}
+
+// O que aconteceu com um asset no último `AssetServer::update` (ver `AssetServer::events`)
+pub enum AssetEvent<T: Asset> {
+    Loaded(Handle<T>),
+    Reloaded(Handle<T>),  // Arquivo alterado em disco; o handle continua o mesmo
+    Failed(Handle<T>),    // Num reload que falhou, o asset mantém o valor anterior
+}
+
+impl<T: Asset> AssetEvent<T> {
+    pub fn handle(&self) -> Handle<T> {
+        match *self {
+            Self::Loaded(handle) | Self::Reloaded(handle) | Self::Failed(handle) => handle,
+        }
+    }
+}
+
+impl<T: Asset> Clone for AssetEvent<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Asset> Copy for AssetEvent<T> {}
+
+impl<T: Asset> PartialEq for AssetEvent<T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other) && self.handle() == other.handle()
+    }
+}
+
+impl<T: Asset> Eq for AssetEvent<T> {}
+
+impl<T: Asset> fmt::Debug for AssetEvent<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loaded(handle) => f.debug_tuple("Loaded").field(handle).finish(),
+            Self::Reloaded(handle) => f.debug_tuple("Reloaded").field(handle).finish(),
+            Self::Failed(handle) => f.debug_tuple("Failed").field(handle).finish(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed,  // Detalhes em `AssetServer::error`
+}
+
+struct Entry<T: Asset> {
+    path: PathBuf,  // Caminho já resolvido contra a raiz
+    sequence: u64,  // Último carregamento pedido; conclusões de pedidos anteriores são descartadas
+    state: LoadState,
+    value: Option<T::Loaded>,
+    error: Option<String>,
+}
+
+// Assets de um tipo
+struct Storage<T: Asset> {
+    entries: Vec<Entry<T>>,
+    by_path: HashMap<PathBuf, Handle<T>>,
+    events: Vec<AssetEvent<T>>,
+}
+
+impl<T: Asset> Default for Storage<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            by_path: HashMap::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+// As storages ficam num mapa por tipo; isto é o que o servidor faz sem saber o tipo
+trait ErasedStorage {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clear_events(&mut self);
+    // Trabalho que relê o asset carregado de `path` (caminho canônico), se houver um
+    fn reload_job(&mut self, path: &Path) -> Option<Job>;
+}
+
+impl<T: Asset> ErasedStorage for Storage<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    fn reload_job(&mut self, path: &Path) -> Option<Job> {
+        let handle = *self.by_path.get(path)?;
+        let entry = &mut self.entries[handle.index as usize];
+        entry.sequence += 1;
+        Some(load_job(handle, entry.path.clone(), entry.sequence))
+    }
+}
+
+// Trabalho de uma thread de fundo: lê e decodifica, devolvendo o que falta fazer na thread principal
+type Job = Box<dyn FnOnce() -> Completion + Send>;
+type Completion = Box<dyn FnOnce(&mut AssetServer, &mut Render) + Send>;
+
+fn load_job<T: Asset>(handle: Handle<T>, path: PathBuf, sequence: u64) -> Job {
+    Box::new(move || {
+        let decoded = read_and_decode::<T>(&path);
+        Box::new(move |server: &mut AssetServer, render: &mut Render| server.finish(handle, decoded, render, &path, sequence))
+    })
+}
+
+// Recarga de uma textura criada direto pelo `Render::load_texture` (atlas, páginas de BMFont)
+fn texture_reload_job(texture: TextureHandle, path: PathBuf, sequence: u64) -> Job {
+    Box::new(move || {
+        let decoded = read_and_decode::<Texture>(&path);
+        Box::new(move |server: &mut AssetServer, render: &mut Render| {
+            server.finish_texture_reload(texture, decoded, render, &path, sequence)
+        })
+    })
+}
+
+fn read_and_decode<T: Asset>(path: &Path) -> Result<T::Decoded> {
+    std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Erro ao ler {}: {}", path.display(), e))
+        .and_then(|bytes| T::decode(bytes, path))
+}
+
+// Carrega assets em threads de fundo e os envia para a GPU no `update`, chamado uma vez por
+// frame (o `AppRunner` faz isso antes do `fixed_update`/`update`).
+//
+//     let texture = ctx.assets.load::<Texture>("images/razor.png");
+//     ...
+//     if let Some(texture) = ctx.assets.texture(texture) { batch.push(texture, sprite) }
+pub struct AssetServer {
+    root: PathBuf,
+    thread_count: usize,
+    storages: HashMap<TypeId, Box<dyn ErasedStorage>>,
+    watcher: Option<FileWatcher>,
+    changed_files: Vec<PathBuf>,  // Arquivos alterados vistos no último `update`
+    pending: usize,  // Carregamentos enviados às threads e ainda não concluídos
+    synchronous: bool,
+    texture_reloads: HashMap<PathBuf, u64>,  // Última recarga pedida de cada textura do `Render`
+    next_texture_reload: u64,
+    jobs: Option<Sender<Job>>,  // Criado com as threads, no primeiro `load`
+    workers: Vec<JoinHandle<()>>,
+    results: (Sender<Completion>, Receiver<Completion>),
+}
+
+impl Default for AssetServer {
+    fn default() -> Self {
+        Self::new(default_root())
+    }
+}
+
+impl AssetServer {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            thread_count: 2,
+            storages: HashMap::new(),
+            watcher: None,
+            changed_files: Vec::new(),
+            pending: 0,
+            synchronous: false,
+            texture_reloads: HashMap::new(),
+            next_texture_reload: 0,
+            jobs: None,
+            workers: Vec::new(),
+            results: mpsc::channel(),
+        }
+    }
+
+    // Quantas threads leem e decodificam arquivos ao mesmo tempo (padrão: 2)
+    pub fn threads(mut self, count: usize) -> Self {
+        self.thread_count = count.max(1);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // Começa a observar a raiz: arquivos alterados são recarregados nos mesmos handles.
+    // Texturas carregadas direto pelo `Render::load_texture` (atlas, páginas de BMFont) também.
+    pub fn watch(&mut self) -> Result<()> {
+        let root = self.root.clone();
+        self.watch_path(root)
+    }
+
+    // Observa também outra pasta (ex.: `RenderConfig::shader_dir`); as mudanças aparecem em `changed_files`
+    pub fn watch_path(&mut self, dir: impl AsRef<Path>) -> Result<()> {
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => self.watcher.insert(FileWatcher::new()?),
+        };
+        watcher.add(dir.as_ref())
+    }
+
+    pub fn unwatch(&mut self) {
+        self.watcher = None;
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    // Caminho completo de um asset (caminhos absolutos são mantidos)
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.root.join(path)
+    }
+
+    // Começa a carregar o arquivo (se ainda não foi pedido) e devolve o handle imediatamente
+    pub fn load<T: Asset>(&mut self, path: impl AsRef<Path>) -> Handle<T> {
+        let path = self.resolve(path);
+        let key = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        let storage = self.storage_mut::<T>();
+        if let Some(handle) = storage.by_path.get(&key) {
+            return *handle;
+        }
+
+        let handle = Handle::new(storage.entries.len() as u32);
+        storage.entries.push(Entry {
+            path: path.clone(),
+            sequence: 0,
+            state: LoadState::Loading,
+            value: None,
+            error: None,
+        });
+        storage.by_path.insert(key, handle);
+        self.submit(load_job(handle, path, 0));
+        handle
+    }
+
+    pub fn load_state<T: Asset>(&self, handle: Handle<T>) -> LoadState {
+        self.entry(handle).map_or(LoadState::Failed, |entry| entry.state)
+    }
+
+    pub fn is_loaded<T: Asset>(&self, handle: Handle<T>) -> bool {
+        self.load_state(handle) == LoadState::Loaded
+    }
+
+    // Mensagem do último erro: de um asset `Failed` ou de um reload que falhou
+    pub fn error<T: Asset>(&self, handle: Handle<T>) -> Option<&str> {
+        self.entry(handle)?.error.as_deref()
+    }
+
+    pub fn path<T: Asset>(&self, handle: Handle<T>) -> Option<&Path> {
+        self.entry(handle).map(|entry| entry.path.as_path())
+    }
+
+    // O asset pronto; `None` enquanto carrega ou se falhou
+    pub fn get<T: Asset>(&self, handle: Handle<T>) -> Option<&T::Loaded> {
+        self.entry(handle)?.value.as_ref()
+    }
+
+    pub fn texture(&self, handle: Handle<Texture>) -> Option<TextureHandle> {
+        self.get(handle).copied()
+    }
+
+    // Eventos de assets do tipo `T` concluídos no último `update`
+    //
+    //     for event in ctx.assets.events::<Texture>() {
+    //         if let AssetEvent::Reloaded(handle) = event { ... }
+    //     }
+    pub fn events<T: Asset>(&self) -> &[AssetEvent<T>] {
+        self.storage::<T>().map_or(&[], |storage| &storage.events)
+    }
+
+    // Arquivos da raiz alterados em disco, vistos no último `update` (caminhos canônicos).
+    // Para recarregar o que não passa pelo servidor, como `TextRenderer::reload_changed`.
+    pub fn changed_files(&self) -> &[PathBuf] {
+        &self.changed_files
+    }
+
+    // Carregamentos ainda em andamento
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.pending == 0
+    }
+
+    // Modo síncrono: o `update` espera os carregamentos pendentes em vez de concluir só os que
+    // já terminaram. Os assets ficam prontos sempre no mesmo frame, independente das threads
+    // (gravação e reprodução de entrada).
+    pub fn set_synchronous(&mut self, synchronous: bool) {
+        self.synchronous = synchronous;
+    }
+
+    pub fn is_synchronous(&self) -> bool {
+        self.synchronous
+    }
+
+    // Conclui os carregamentos que terminaram nas threads (envio para a GPU) e dispara a
+    // recarga dos arquivos alterados, que também são lidos e decodificados nas threads.
+    // Não bloqueia. Os eventos valem até o próximo `update`.
+    pub fn update(&mut self, render: &mut Render) {
+        for storage in self.storages.values_mut() {
+            storage.clear_events();
+        }
+        self.changed_files = match &mut self.watcher {
+            Some(watcher) => watcher.changed_files(),
+            None => Vec::new(),
+        };
+        for path in std::mem::take(&mut self.changed_files) {
+            if let Some(texture) = render.textures().handle_for_path(&path) {
+                self.next_texture_reload += 1;
+                self.texture_reloads.insert(path.clone(), self.next_texture_reload);
+                self.submit(texture_reload_job(texture, path.clone(), self.next_texture_reload));
+            }
+            let jobs: Vec<Job> = self.storages.values_mut().filter_map(|storage| storage.reload_job(&path)).collect();
+            for job in jobs {
+                self.submit(job);
+            }
+            self.changed_files.push(path);
+        }
+
+        while let Ok(completion) = self.results.1.try_recv() {
+            self.pending -= 1;
+            completion(self, render);
+        }
+        if self.synchronous {
+            self.wait(render);
+        }
+    }
+
+    // Espera todos os carregamentos pendentes terminarem (telas de carregamento, testes)
+    pub fn wait(&mut self, render: &mut Render) {
+        while self.pending > 0 {
+            let Ok(completion) = self.results.1.recv() else {
+                break;
+            };
+            self.pending -= 1;
+            completion(self, render);
+        }
+    }
+
+    fn submit(&mut self, job: Job) {
+        let results = self.results.0.clone();
+        let jobs = self.start_workers();
+        if let Err(mpsc::SendError(job)) = jobs.send(job) {
+            // Sem threads (não deve acontecer): carrega aqui mesmo
+            let _ = results.send(job());
+        }
+        self.pending += 1;
+    }
+
+    fn start_workers(&mut self) -> &Sender<Job> {
+        if self.jobs.is_none() {
+            let (sender, receiver) = mpsc::channel::<Job>();
+            let receiver = Arc::new(Mutex::new(receiver));
+            for i in 0..self.thread_count {
+                let receiver = Arc::clone(&receiver);
+                let results = self.results.0.clone();
+                let worker = thread::Builder::new()
+                    .name(format!("asset-loader-{}", i))
+                    .spawn(move || loop {
+                        // O lock só é mantido enquanto espera o próximo trabalho
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        let Ok(job) = job else {
+                            break;  // `AssetServer` descartado
+                        };
+                        if results.send(job()).is_err() {
+                            break;
+                        }
+                    })
+                    .expect("failed to spawn asset loader thread");
+                self.workers.push(worker);
+            }
+            self.jobs = Some(sender);
+        }
+        self.jobs.as_ref().expect("asset loader threads not started")
+    }
+
+    // Upload (ou reload, se o asset já estava carregado) na thread principal
+    fn finish<T: Asset>(&mut self, handle: Handle<T>, decoded: Result<T::Decoded>, render: &mut Render, path: &Path, sequence: u64) {
+        let storage = self.storage_mut::<T>();
+        let Some(entry) = storage.entries.get_mut(handle.index as usize) else {
+            return;
+        };
+        // As threads podem terminar fora de ordem: um arquivo salvo duas vezes seguidas não pode
+        // terminar com o conteúdo da primeira leitura
+        if sequence != entry.sequence {
+            return;
+        }
+        let result = match (decoded, entry.value.as_mut()) {
+            (Ok(decoded), Some(value)) => T::reload(decoded, value, render, path).map(|()| AssetEvent::Reloaded(handle)),
+            (Ok(decoded), None) => T::upload(decoded, render, path).map(|value| {
+                entry.value = Some(value);
+                AssetEvent::Loaded(handle)
+            }),
+            (Err(e), _) => Err(e),
+        };
+        let event = match result {
+            Ok(event) => {
+                entry.state = LoadState::Loaded;
+                entry.error = None;
+                event
+            }
+            Err(e) => {
+                eprintln!("Asset error: {}", e);
+                if entry.value.is_none() {
+                    entry.state = LoadState::Failed;
+                }
+                entry.error = Some(e.to_string());
+                AssetEvent::Failed(handle)
+            }
+        };
+        storage.events.push(event);
+    }
+
+    fn finish_texture_reload(
+        &mut self,
+        texture: TextureHandle,
+        decoded: Result<image::RgbaImage>,
+        render: &mut Render,
+        path: &Path,
+        sequence: u64,
+    ) {
+        if self.texture_reloads.get(path) != Some(&sequence) {
+            return;
+        }
+        self.texture_reloads.remove(path);
+        match decoded {
+            Ok(img) => {
+                render.replace_texture(texture, &img);
+            }
+            Err(e) => eprintln!("Asset error: {}", e),
+        }
+    }
+
+    fn entry<T: Asset>(&self, handle: Handle<T>) -> Option<&Entry<T>> {
+        self.storage::<T>()?.entries.get(handle.index as usize)
+    }
+
+    fn storage<T: Asset>(&self) -> Option<&Storage<T>> {
+        self.storages.get(&TypeId::of::<T>())?.as_any().downcast_ref::<Storage<T>>()
+    }
+
+    fn storage_mut<T: Asset>(&mut self) -> &mut Storage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Storage::<T>::default()))
+            .as_any_mut()
+            .downcast_mut::<Storage<T>>()
+            .expect("asset storage type mismatch")
+    }
+}
+
+impl Drop for AssetServer {
+    fn drop(&mut self) {
+        // Fecha a fila para as threads saírem e espera os arquivos em leitura
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+// Arquivos de dados (manifestos, mapas de ações, gravações) são RON pela extensão `.ron`
+// e JSON em qualquer outro caso
+pub(crate) fn is_ron(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ron"))
+}
+
+// Raiz padrão: a variável `ASSET_ROOT`; senão a pasta `assets` ao lado do executável; senão
+// `assets` (ou `src/assets`) no diretório do pacote, quando rodando pelo `cargo run`;
+// senão `assets` relativo ao diretório atual
+pub fn default_root() -> PathBuf {
+    if let Some(root) = std::env::var_os(ASSET_ROOT_VAR) {
+        return PathBuf::from(root);
+    }
+
+    let mut candidates = Vec::new();
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        candidates.push(dir.join("assets"));
+    }
+    if let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from) {
+        candidates.push(dir.join("assets"));
+        candidates.push(dir.join("src").join("assets"));
+    }
+    candidates
+        .into_iter()
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| PathBuf::from("assets"))
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use notify::{EventKind, RecursiveMode, Watcher};
+
+// Quanto tempo um arquivo precisa ficar sem mudar para ser recarregado. Editores costumam
+// salvar em várias etapas (truncar, escrever, renomear); ler no meio disso daria erro.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+// Observa pastas (inotify no Linux) e junta as mudanças de cada arquivo
+pub(crate) struct FileWatcher {
+    watcher: notify::RecommendedWatcher,  // Para de observar quando descartado
+    events: Receiver<notify::Result<notify::Event>>,
+    dirty: HashMap<PathBuf, Instant>,  // Arquivo alterado -> última mudança vista
+}
+
+impl FileWatcher {
+    pub(crate) fn new() -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+        Ok(Self {
+            watcher,
+            events,
+            dirty: HashMap::new(),
+        })
+    }
+
+    // Passa a observar `dir` e suas subpastas
+    pub(crate) fn add(&mut self, dir: &Path) -> Result<()> {
+        self.watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| anyhow::anyhow!("Erro ao observar {}: {}", dir.display(), e))
+    }
+
+    // Arquivos alterados que já pararam de mudar (caminhos canônicos)
+    pub(crate) fn changed_files(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                    for path in event.paths {
+                        self.dirty.insert(path, now);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Asset watcher error: {}", e),
+            }
+        }
+
+        let mut changed = Vec::new();
+        self.dirty.retain(|path, last_change| {
+            if now.duration_since(*last_change) < DEBOUNCE {
+                return true;
+            }
+            // Arquivos removidos (ou renomeados para outro lugar) não têm o que recarregar
+            if let Ok(path) = std::fs::canonicalize(path) {
+                if path.is_file() && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+            false
+        });
+        changed.sort();
+        changed
+    }
+}
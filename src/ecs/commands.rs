@@ -0,0 +1,157 @@
+use super::entity::{Entities, Entity};
+use super::world::{Bundle, Component, World};
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+// Mudanças estruturais guardadas para depois: sistemas rodam com `&World` e não podem criar
+// ou remover entidades/componentes na hora
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    // Aplica na ordem em que os comandos foram enfileirados
+    pub fn apply(&mut self, world: &mut World) {
+        world.flush();
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+// Enfileira comandos para o fim do sistema. Entidades criadas aqui já têm id, mas só
+// existem (e aparecem nas queries) depois que a fila é aplicada.
+pub struct Commands<'w, 's> {
+    queue: &'s mut CommandQueue,
+    entities: &'w Entities,
+}
+
+impl<'w, 's> Commands<'w, 's> {
+    pub fn new(queue: &'s mut CommandQueue, world: &'w World) -> Self {
+        Self {
+            queue,
+            entities: world.entity_allocator(),
+        }
+    }
+
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        let entity = self.entities.reserve();
+        self.queue.push(move |world| {
+            world.insert(entity, bundle);
+        });
+        entity
+    }
+
+    pub fn spawn_empty(&mut self) -> Entity {
+        self.entities.reserve()
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    pub fn insert(&mut self, entity: Entity, bundle: impl Bundle) {
+        self.queue.push(move |world| {
+            world.insert(entity, bundle);
+        });
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    pub fn insert_resource<R: Component>(&mut self, resource: R) {
+        self.queue.push(move |world| {
+            world.insert_resource(resource);
+        });
+    }
+
+    pub fn remove_resource<R: Component>(&mut self) {
+        self.queue.push(|world| {
+            world.remove_resource::<R>();
+        });
+    }
+
+    // Comando arbitrário com acesso total ao `World`
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(command);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Health(i32);
+
+    #[test]
+    fn spawned_entities_exist_after_apply() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let (a, b) = {
+            let mut commands = Commands::new(&mut queue, &world);
+            (commands.spawn((Health(1),)), commands.spawn((Health(2),)))
+        };
+        assert_ne!(a, b);
+        assert!(!world.is_alive(a));
+        assert_eq!(queue.len(), 2);
+
+        queue.apply(&mut world);
+        assert!(queue.is_empty());
+        assert_eq!(*world.get::<Health>(a).unwrap(), Health(1));
+        assert_eq!(*world.get::<Health>(b).unwrap(), Health(2));
+    }
+
+    #[test]
+    fn reserved_ids_do_not_collide_with_direct_spawns() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let reserved = Commands::new(&mut queue, &world).spawn((Health(1),));
+        let direct = world.spawn((Health(2),));
+        assert_ne!(reserved, direct);
+
+        queue.apply(&mut world);
+        assert_eq!(*world.get::<Health>(reserved).unwrap(), Health(1));
+        assert_eq!(*world.get::<Health>(direct).unwrap(), Health(2));
+    }
+
+    #[test]
+    fn commands_apply_in_order() {
+        let mut world = World::new();
+        let entity = world.spawn((Health(1),));
+        let mut queue = CommandQueue::default();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.insert(entity, (Health(2),));
+            commands.remove::<Health>(entity);
+            commands.insert(entity, (Health(3),));
+            commands.insert_resource(Health(10));
+            commands.add(|world| world.get_resource_mut::<Health>().unwrap().0 += 1);
+        }
+        queue.apply(&mut world);
+        assert_eq!(*world.get::<Health>(entity).unwrap(), Health(3));
+        assert_eq!(*world.resource::<Health>().unwrap(), Health(11));
+
+        Commands::new(&mut queue, &world).despawn(entity);
+        queue.apply(&mut world);
+        assert!(!world.is_alive(entity));
+    }
+}
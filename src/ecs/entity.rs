@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Identificador de uma entidade. A geração evita que um id antigo aponte para outra
+// entidade depois de um `despawn` (mesma ideia do `TextureHandle`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot {
+    generation: u32,
+    alive: bool,
+}
+
+// Alocador de entidades. Ids podem ser reservados com `&self` (pelos `Commands`, durante os
+// sistemas) e passam a existir de fato no próximo `flush`.
+#[derive(Default)]
+pub(crate) struct Entities {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    reserved: AtomicU32,  // Ids novos reservados depois do fim de `slots`
+    len: usize,
+}
+
+impl Entities {
+    pub fn alloc(&mut self) -> Entity {
+        self.flush();
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.alive = true;
+                Entity { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, alive: true });
+                Entity {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    // Reserva um id sem precisar de `&mut`; nunca reaproveita slots livres
+    pub fn reserve(&self) -> Entity {
+        let offset = self.reserved.fetch_add(1, Ordering::Relaxed);
+        Entity {
+            index: self.slots.len() as u32 + offset,
+            generation: 0,
+        }
+    }
+
+    // Torna vivas as entidades reservadas
+    pub fn flush(&mut self) {
+        let reserved = std::mem::take(self.reserved.get_mut());
+        for _ in 0..reserved {
+            self.slots.push(Slot { generation: 0, alive: true });
+        }
+        self.len += reserved as usize;
+    }
+
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let slot = &mut self.slots[entity.index as usize];
+        slot.alive = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(entity.index);
+        self.len -= 1;
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.slots
+            .get(entity.index as usize)
+            .is_some_and(|slot| slot.alive && slot.generation == entity.generation)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.alive)
+            .map(|(index, slot)| Entity {
+                index: index as u32,
+                generation: slot.generation,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_slot_is_reused_with_new_generation() {
+        let mut entities = Entities::default();
+        let first = entities.alloc();
+        assert!(entities.free(first));
+        assert!(!entities.free(first));
+
+        let second = entities.alloc();
+        assert_eq!(second.index(), first.index());
+        assert_eq!(second.generation(), first.generation() + 1);
+        assert!(!entities.is_alive(first));
+        assert!(entities.is_alive(second));
+        assert_eq!(entities.len(), 1);
+    }
+
+    #[test]
+    fn reserved_entities_exist_after_flush() {
+        let mut entities = Entities::default();
+        let freed = entities.alloc();
+        entities.free(freed);
+
+        let a = entities.reserve();
+        let b = entities.reserve();
+        assert_ne!(a, b);
+        assert_ne!(a.index(), freed.index());  // Reservas não usam slots livres
+        assert!(!entities.is_alive(a));
+
+        entities.flush();
+        assert!(entities.is_alive(a));
+        assert!(entities.is_alive(b));
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities.iter().collect::<Vec<_>>(), vec![a, b]);
+    }
+
+    #[test]
+    fn alloc_flushes_reservations_first() {
+        let mut entities = Entities::default();
+        let reserved = entities.reserve();
+        let allocated = entities.alloc();
+        assert_ne!(reserved, allocated);
+        assert!(entities.is_alive(reserved));
+        assert!(entities.is_alive(allocated));
+    }
+}
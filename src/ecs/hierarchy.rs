@@ -0,0 +1,191 @@
+use super::commands::Commands;
+use super::entity::Entity;
+use super::transform::Transform2D;
+use super::world::World;
+
+// Pai de uma entidade. Mantido junto com o `Children` do pai pelas funções de `Hierarchy`;
+// não deve ser inserido diretamente.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Parent(Entity);
+
+impl Parent {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+// Filhos de uma entidade, na ordem em que foram adicionados
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Children(Vec<Entity>);
+
+impl Children {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+}
+
+// Relações pai/filho no `World`
+pub trait Hierarchy {
+    // Torna `child` filho de `parent` (saindo do pai anterior). Recusa ciclos e entidades mortas.
+    fn set_parent(&mut self, child: Entity, parent: Entity) -> bool;
+    fn remove_parent(&mut self, child: Entity);
+    // Remove a entidade e todos os descendentes
+    fn despawn_recursive(&mut self, entity: Entity);
+    fn is_ancestor_of(&self, ancestor: Entity, entity: Entity) -> bool;
+}
+
+impl Hierarchy for World {
+    fn set_parent(&mut self, child: Entity, parent: Entity) -> bool {
+        self.flush();
+        if !self.is_alive(child) || !self.is_alive(parent) || child == parent || self.is_ancestor_of(child, parent) {
+            return false;
+        }
+        self.remove_parent(child);
+        self.insert(child, (Parent(parent),));
+        match self.get_mut::<Children>(parent) {
+            Some(mut children) => children.0.push(child),
+            None => {
+                self.insert(parent, (Children(vec![child]),));
+            }
+        }
+        true
+    }
+
+    fn remove_parent(&mut self, child: Entity) {
+        let Some(Parent(parent)) = self.remove::<Parent>(child) else {
+            return;
+        };
+        // Remoções não aparecem no `Changed`: força o recálculo do transform global do novo raiz
+        if let Some(mut transform) = self.get_mut::<Transform2D>(child) {
+            transform.set_changed();
+        }
+        let now_empty = match self.get_mut::<Children>(parent) {
+            Some(mut children) => {
+                children.0.retain(|&entity| entity != child);
+                children.0.is_empty()
+            }
+            None => false,
+        };
+        if now_empty {
+            self.remove::<Children>(parent);
+        }
+    }
+
+    fn despawn_recursive(&mut self, entity: Entity) {
+        self.remove_parent(entity);
+        let mut pending = vec![entity];
+        while let Some(entity) = pending.pop() {
+            if let Some(children) = self.remove::<Children>(entity) {
+                pending.extend(children.0);
+            }
+            self.despawn(entity);
+        }
+    }
+
+    fn is_ancestor_of(&self, ancestor: Entity, entity: Entity) -> bool {
+        let mut current = entity;
+        while let Some(parent) = self.get::<Parent>(current).map(|parent| parent.get()) {
+            if parent == ancestor {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+}
+
+// Tira a entidade da hierarquia antes de ela ser removida, para não deixar `Parent`/`Children`
+// apontando para um id morto
+pub(crate) fn detach(world: &mut World, entity: Entity) {
+    world.remove_parent(entity);
+    let Some(children) = world.remove::<Children>(entity) else {
+        return;
+    };
+    for child in children.0 {
+        world.remove::<Parent>(child);
+        if let Some(mut transform) = world.get_mut::<Transform2D>(child) {
+            transform.set_changed();
+        }
+    }
+}
+
+// As mesmas operações, adiadas para o fim do sistema
+pub trait HierarchyCommands {
+    fn set_parent(&mut self, child: Entity, parent: Entity);
+    fn remove_parent(&mut self, child: Entity);
+    fn despawn_recursive(&mut self, entity: Entity);
+}
+
+impl HierarchyCommands for Commands<'_, '_> {
+    fn set_parent(&mut self, child: Entity, parent: Entity) {
+        self.add(move |world| {
+            world.set_parent(child, parent);
+        });
+    }
+
+    fn remove_parent(&mut self, child: Entity) {
+        self.add(move |world| world.remove_parent(child));
+    }
+
+    fn despawn_recursive(&mut self, entity: Entity) {
+        self.add(move |world| world.despawn_recursive(entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::*;
+    use crate::ecs::system::{IntoSystem, System};
+    use crate::ecs::transform::{propagate_transforms, GlobalTransform};
+
+    #[test]
+    fn despawn_detaches_parent_and_children() {
+        let mut world = World::new();
+        let grandparent = world.spawn(());
+        let parent = world.spawn(());
+        let child = world.spawn(());
+        assert!(world.set_parent(parent, grandparent));
+        assert!(world.set_parent(child, parent));
+
+        assert!(world.despawn(parent));
+        assert!(world.get::<Children>(grandparent).is_none());
+        assert!(world.get::<Parent>(child).is_none());
+        assert!(world.is_alive(child));
+    }
+
+    #[test]
+    fn orphans_become_roots_for_propagation() {
+        let mut world = World::new();
+        let parent = world.spawn(Transform2D::from_xy(10.0, 0.0).bundle());
+        let child = world.spawn(Transform2D::from_xy(1.0, 0.0).bundle());
+        world.set_parent(child, parent);
+
+        let mut system = propagate_transforms.into_system();
+        system.initialize(&mut world);
+        system.run(&world);
+        assert_eq!(world.get::<GlobalTransform>(child).unwrap().translation(), Vec2::new(11.0, 0.0));
+
+        world.despawn(parent);
+        system.run(&world);
+        assert_eq!(world.get::<GlobalTransform>(child).unwrap().translation(), Vec2::new(1.0, 0.0));
+
+        // O filho continua acompanhando o próprio transform
+        world.get_mut::<Transform2D>(child).unwrap().translate(Vec2::new(2.0, 0.0));
+        system.run(&world);
+        assert_eq!(world.get::<GlobalTransform>(child).unwrap().translation(), Vec2::new(3.0, 0.0));
+    }
+}
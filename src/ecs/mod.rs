@@ -0,0 +1,31 @@
+// Entity Component System: entidades são ids, componentes ficam em sparse sets por tipo
+// e sistemas são funções cujos argumentos (queries, recursos, comandos) dizem o que acessam.
+//
+//     let mut world = World::new();
+//     world.spawn((Position(Vec2::ZERO), Velocity(Vec2::X)));
+//     let mut schedule = Schedule::new();
+//     schedule.add_system(mover);
+//     schedule.run(&mut world);
+pub mod commands;
+pub mod entity;
+pub mod hierarchy;
+pub mod query;
+pub mod schedule;
+pub mod sprite;
+mod storage;
+pub mod system;
+pub mod transform;
+pub mod world;
+
+pub use commands::{CommandQueue, Commands};
+pub use entity::Entity;
+pub use hierarchy::{Children, Hierarchy, HierarchyCommands, Parent};
+pub use query::{Added, Changed, Query, QueryData, QueryFilter, ReadOnlyQueryData, With, Without};
+pub use schedule::{
+    resource_exists, resource_equals, run_once, ExecutorKind, IntoSystemConfig, Schedule, SystemConfig, DEFAULT_STAGE,
+};
+pub use sprite::{extract_sprites, render_sprites, Sprite};
+pub use storage::ComponentTicks;
+pub use system::{Access, IntoSystem, System, SystemParam};
+pub use transform::{propagate_transforms, GlobalTransform, Transform2D};
+pub use world::{Bundle, Component, Mut, Ref, Res, ResMut, World};
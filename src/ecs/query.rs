@@ -0,0 +1,593 @@
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use super::entity::Entity;
+use super::storage::SparseSet;
+use super::system::Access;
+use super::world::{Component, Mut, World};
+
+// Janela de detecção de mudanças: `Changed`/`Added` aceitam ticks mais novos que `last_run`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ticks {
+    pub last_run: u64,
+    pub this_run: u64,
+}
+
+impl Ticks {
+    pub fn is_newer(&self, tick: u64) -> bool {
+        tick > self.last_run
+    }
+}
+
+// O que uma query devolve por entidade: `&T`, `&mut T`, `Option<&T>`, `Entity` ou tuplas deles.
+// `borrow` pega os locks das storages uma vez; `fetch` monta o item de cada entidade.
+pub trait QueryData {
+    type Borrow<'w>;
+    type Item<'a>;
+
+    fn access(access: &mut Access);
+    fn borrow(world: &World) -> Self::Borrow<'_>;
+
+    // Entidades que podem satisfazer a query (a storage do componente obrigatório);
+    // `None` se não houver restrição
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]>;
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity) -> bool;
+
+    // Unsafe: a entidade precisa ter passado no `matches` e nenhum outro item mutável dela pode estar vivo
+    #[allow(clippy::missing_safety_doc)]
+    unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> Self::Item<'a>;
+}
+
+// Queries que só leem: podem ser iteradas com `&self` e ter vários itens vivos da mesma entidade.
+// Unsafe: só pode ser implementada se o `fetch` nunca devolver acesso mutável.
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait ReadOnlyQueryData: QueryData {}
+
+// Restrição extra que não devolve dados: `With<T>`, `Without<T>`, `Changed<T>`, `Added<T>` ou tuplas deles
+pub trait QueryFilter {
+    type Borrow<'w>;
+
+    fn access(access: &mut Access);
+    fn borrow(world: &World) -> Self::Borrow<'_>;
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]>;
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> bool;
+}
+
+pub struct ReadBorrow<'w, T> {
+    storage: &'w SparseSet<T>,
+    data: RwLockReadGuard<'w, Vec<T>>,
+}
+
+fn read_borrow<T: Component>(world: &World) -> Option<ReadBorrow<'_, T>> {
+    let storage = world.storage::<T>()?;
+    Some(ReadBorrow {
+        storage,
+        data: storage.read(),
+    })
+}
+
+// O ponteiro permite devolver `&mut` de entidades diferentes a partir de um único lock de escrita
+pub struct WriteBorrow<'w, T> {
+    storage: &'w SparseSet<T>,
+    _guard: RwLockWriteGuard<'w, Vec<T>>,
+    data: *mut T,
+}
+
+fn write_borrow<T: Component>(world: &World) -> Option<WriteBorrow<'_, T>> {
+    let storage = world.storage::<T>()?;
+    let mut guard = storage.write();
+    let data = guard.as_mut_ptr();
+    Some(WriteBorrow {
+        storage,
+        _guard: guard,
+        data,
+    })
+}
+
+// Storage ausente: nenhum candidato
+fn entities_of<T>(storage: Option<&SparseSet<T>>) -> &[Entity] {
+    storage.map_or(&[], |storage| storage.entities())
+}
+
+impl<T: Component> QueryData for &T {
+    type Borrow<'w> = Option<ReadBorrow<'w, T>>;
+    type Item<'a> = &'a T;
+
+    fn access(access: &mut Access) {
+        access.read_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        read_borrow::<T>(world)
+    }
+
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        Some(entities_of(borrow.as_ref().map(|borrow| borrow.storage)))
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity) -> bool {
+        borrow.as_ref().is_some_and(|borrow| borrow.storage.contains(entity))
+    }
+
+    unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, _ticks: Ticks) -> &'a T {
+        let borrow = borrow.as_ref().expect("query fetch without match");
+        let dense = borrow.storage.dense_index(entity).expect("query fetch without match");
+        &borrow.data[dense]
+    }
+}
+
+unsafe impl<T: Component> ReadOnlyQueryData for &T {}
+
+impl<T: Component> QueryData for &mut T {
+    type Borrow<'w> = Option<WriteBorrow<'w, T>>;
+    type Item<'a> = Mut<'a, T>;
+
+    fn access(access: &mut Access) {
+        access.write_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        write_borrow::<T>(world)
+    }
+
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        Some(entities_of(borrow.as_ref().map(|borrow| borrow.storage)))
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity) -> bool {
+        borrow.as_ref().is_some_and(|borrow| borrow.storage.contains(entity))
+    }
+
+    unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> Mut<'a, T> {
+        let borrow = borrow.as_ref().expect("query fetch without match");
+        let dense = borrow.storage.dense_index(entity).expect("query fetch without match");
+        Mut {
+            value: &mut *borrow.data.add(dense),
+            changed: borrow.storage.changed_tick(dense),
+            added: borrow.storage.ticks(dense).added,
+            tick: ticks.this_run,
+        }
+    }
+}
+
+impl<T: Component> QueryData for Option<&T> {
+    type Borrow<'w> = Option<ReadBorrow<'w, T>>;
+    type Item<'a> = Option<&'a T>;
+
+    fn access(access: &mut Access) {
+        access.read_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        read_borrow::<T>(world)
+    }
+
+    fn candidates<'a>(_borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        None
+    }
+
+    fn matches(_borrow: &Self::Borrow<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, _ticks: Ticks) -> Option<&'a T> {
+        let borrow = borrow.as_ref()?;
+        let dense = borrow.storage.dense_index(entity)?;
+        Some(&borrow.data[dense])
+    }
+}
+
+unsafe impl<T: Component> ReadOnlyQueryData for Option<&T> {}
+
+impl<T: Component> QueryData for Option<&mut T> {
+    type Borrow<'w> = Option<WriteBorrow<'w, T>>;
+    type Item<'a> = Option<Mut<'a, T>>;
+
+    fn access(access: &mut Access) {
+        access.write_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        write_borrow::<T>(world)
+    }
+
+    fn candidates<'a>(_borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        None
+    }
+
+    fn matches(_borrow: &Self::Borrow<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> Option<Mut<'a, T>> {
+        let borrow = borrow.as_ref()?;
+        let dense = borrow.storage.dense_index(entity)?;
+        Some(Mut {
+            value: &mut *borrow.data.add(dense),
+            changed: borrow.storage.changed_tick(dense),
+            added: borrow.storage.ticks(dense).added,
+            tick: ticks.this_run,
+        })
+    }
+}
+
+impl QueryData for Entity {
+    type Borrow<'w> = ();
+    type Item<'a> = Entity;
+
+    fn access(_access: &mut Access) {}
+
+    fn borrow(_world: &World) -> Self::Borrow<'_> {}
+
+    fn candidates<'a>(_borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        None
+    }
+
+    fn matches(_borrow: &Self::Borrow<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch(_borrow: &Self::Borrow<'_>, entity: Entity, _ticks: Ticks) -> Entity {
+        entity
+    }
+}
+
+unsafe impl ReadOnlyQueryData for Entity {}
+
+// Fica com a menor lista de candidatos
+fn narrow<'a>(best: Option<&'a [Entity]>, other: Option<&'a [Entity]>) -> Option<&'a [Entity]> {
+    match (best, other) {
+        (Some(best), Some(other)) if other.len() < best.len() => Some(other),
+        (None, other) => other,
+        (best, _) => best,
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, unused_mut, clippy::unused_unit)]
+        impl<$($name: QueryData),*> QueryData for ($($name,)*) {
+            type Borrow<'w> = ($($name::Borrow<'w>,)*);
+            type Item<'a> = ($($name::Item<'a>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn borrow(world: &World) -> Self::Borrow<'_> {
+                ($($name::borrow(world),)*)
+            }
+
+            fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+                let ($($name,)*) = borrow;
+                let mut best = None;
+                $(best = narrow(best, $name::candidates($name));)*
+                best
+            }
+
+            fn matches(borrow: &Self::Borrow<'_>, entity: Entity) -> bool {
+                let ($($name,)*) = borrow;
+                true $(&& $name::matches($name, entity))*
+            }
+
+            unsafe fn fetch<'a>(borrow: &'a Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> Self::Item<'a> {
+                let ($($name,)*) = borrow;
+                ($($name::fetch($name, entity, ticks),)*)
+            }
+        }
+
+        unsafe impl<$($name: ReadOnlyQueryData),*> ReadOnlyQueryData for ($($name,)*) {}
+
+        #[allow(non_snake_case, unused_variables, unused_mut, clippy::unused_unit)]
+        impl<$($name: QueryFilter),*> QueryFilter for ($($name,)*) {
+            type Borrow<'w> = ($($name::Borrow<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn borrow(world: &World) -> Self::Borrow<'_> {
+                ($($name::borrow(world),)*)
+            }
+
+            fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+                let ($($name,)*) = borrow;
+                let mut best = None;
+                $(best = narrow(best, $name::candidates($name));)*
+                best
+            }
+
+            fn matches(borrow: &Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> bool {
+                let ($($name,)*) = borrow;
+                true $(&& $name::matches($name, entity, ticks))*
+            }
+        }
+    };
+}
+
+impl_query_tuple!();
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+impl_query_tuple!(A, B, C, D, E, F, G);
+impl_query_tuple!(A, B, C, D, E, F, G, H);
+
+// Entidades que têm `T` (sem emprestar o componente)
+pub struct With<T>(PhantomData<T>);
+
+// Entidades que não têm `T`
+pub struct Without<T>(PhantomData<T>);
+
+// Entidades cujo `T` foi alterado (ou adicionado) desde a última execução do sistema
+pub struct Changed<T>(PhantomData<T>);
+
+// Entidades que ganharam `T` desde a última execução do sistema
+pub struct Added<T>(PhantomData<T>);
+
+// A estrutura das storages só muda com `&mut World`: `With`/`Without` não precisam de acesso
+impl<T: Component> QueryFilter for With<T> {
+    type Borrow<'w> = Option<&'w SparseSet<T>>;
+
+    fn access(_access: &mut Access) {}
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        world.storage::<T>()
+    }
+
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        Some(entities_of(*borrow))
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity, _ticks: Ticks) -> bool {
+        borrow.is_some_and(|storage| storage.contains(entity))
+    }
+}
+
+impl<T: Component> QueryFilter for Without<T> {
+    type Borrow<'w> = Option<&'w SparseSet<T>>;
+
+    fn access(_access: &mut Access) {}
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        world.storage::<T>()
+    }
+
+    fn candidates<'a>(_borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        None
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity, _ticks: Ticks) -> bool {
+        !borrow.is_some_and(|storage| storage.contains(entity))
+    }
+}
+
+// Os ticks mudam junto com os dados: `Changed`/`Added` contam como leitura de `T`
+impl<T: Component> QueryFilter for Changed<T> {
+    type Borrow<'w> = Option<&'w SparseSet<T>>;
+
+    fn access(access: &mut Access) {
+        access.read_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        world.storage::<T>()
+    }
+
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        Some(entities_of(*borrow))
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> bool {
+        borrow
+            .and_then(|storage| Some(storage.ticks(storage.dense_index(entity)?)))
+            .is_some_and(|component| ticks.is_newer(component.changed))
+    }
+}
+
+impl<T: Component> QueryFilter for Added<T> {
+    type Borrow<'w> = Option<&'w SparseSet<T>>;
+
+    fn access(access: &mut Access) {
+        access.read_component(TypeId::of::<T>());
+    }
+
+    fn borrow(world: &World) -> Self::Borrow<'_> {
+        world.storage::<T>()
+    }
+
+    fn candidates<'a>(borrow: &'a Self::Borrow<'_>) -> Option<&'a [Entity]> {
+        Some(entities_of(*borrow))
+    }
+
+    fn matches(borrow: &Self::Borrow<'_>, entity: Entity, ticks: Ticks) -> bool {
+        borrow
+            .and_then(|storage| Some(storage.ticks(storage.dense_index(entity)?)))
+            .is_some_and(|component| ticks.is_newer(component.added))
+    }
+}
+
+// Visão das entidades que têm os componentes de `Q` e passam no filtro `F`.
+// Os locks das storages ficam presos enquanto a query existir.
+pub struct Query<'w, Q: QueryData, F: QueryFilter = ()> {
+    world: &'w World,
+    data: Q::Borrow<'w>,
+    filter: F::Borrow<'w>,
+    ticks: Ticks,
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> Query<'w, Q, F> {
+    pub(crate) fn new(world: &'w World, ticks: Ticks) -> Self {
+        Self {
+            world,
+            data: Q::borrow(world),
+            filter: F::borrow(world),
+            ticks,
+        }
+    }
+
+    fn candidates(&self) -> impl Iterator<Item = Entity> + '_ {
+        let slice = narrow(Q::candidates(&self.data), F::candidates(&self.filter));
+        let all = match slice {
+            Some(_) => None,
+            None => Some(self.world.entities()),
+        };
+        slice
+            .into_iter()
+            .flatten()
+            .copied()
+            .chain(all.into_iter().flatten())
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.world.is_alive(entity) && Q::matches(&self.data, entity) && F::matches(&self.filter, entity, self.ticks)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Q::Item<'_>> + use<'_, 'w, Q, F>
+    where
+        Q: ReadOnlyQueryData,
+    {
+        self.candidates()
+            .filter(move |&entity| self.contains(entity))
+            .map(move |entity| unsafe { Q::fetch(&self.data, entity, self.ticks) })
+    }
+
+    // Cada entidade aparece uma vez, então os itens mutáveis nunca se sobrepõem
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = Q::Item<'_>> + use<'_, 'w, Q, F> {
+        let this = &*self;
+        this.candidates()
+            .filter(move |&entity| this.contains(entity))
+            .map(move |entity| unsafe { Q::fetch(&this.data, entity, this.ticks) })
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<Q::Item<'_>>
+    where
+        Q: ReadOnlyQueryData,
+    {
+        self.contains(entity)
+            .then(|| unsafe { Q::fetch(&self.data, entity, self.ticks) })
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        self.contains(entity)
+            .then(|| unsafe { Q::fetch(&self.data, entity, self.ticks) })
+    }
+
+    // O único resultado; `None` se não houver nenhum ou houver mais de um
+    pub fn single(&self) -> Option<Q::Item<'_>>
+    where
+        Q: ReadOnlyQueryData,
+    {
+        let mut iter = self.iter();
+        let item = iter.next()?;
+        iter.next().is_none().then_some(item)
+    }
+
+    pub fn single_mut(&mut self) -> Option<Q::Item<'_>> {
+        let entity = {
+            let mut entities = self.candidates().filter(|&entity| self.contains(entity));
+            let entity = entities.next()?;
+            if entities.next().is_some() {
+                return None;
+            }
+            entity
+        };
+        self.get_mut(entity)
+    }
+
+    pub fn count(&self) -> usize {
+        self.candidates().filter(|&entity| self.contains(entity)).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.candidates().any(|entity| self.contains(entity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    struct Frozen;
+
+    #[test]
+    fn filters_select_entities() {
+        let mut world = World::new();
+        let moving = world.spawn((Position(0), Velocity(1)));
+        let frozen = world.spawn((Position(0), Velocity(1), Frozen));
+        let still = world.spawn((Position(5),));
+
+        let query = world.query_filtered::<Entity, (With<Velocity>, Without<Frozen>)>();
+        assert_eq!(query.iter().collect::<Vec<_>>(), vec![moving]);
+        assert!(!query.contains(frozen));
+
+        let query = world.query::<(Entity, Option<&Velocity>)>();
+        assert_eq!(query.count(), 3);
+        assert_eq!(query.get(still).unwrap().1, None);
+
+        assert_eq!(world.query_filtered::<&Position, Without<Velocity>>().single(), Some(&Position(5)));
+        assert!(world.query::<&Position>().single().is_none());
+        assert!(world.query::<&Frozen>().get(moving).is_none());
+    }
+
+    #[test]
+    fn iter_mut_writes_components() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Velocity(2)));
+        for (mut position, velocity) in world.query::<(&mut Position, &Velocity)>().iter_mut() {
+            position.0 += velocity.0;
+        }
+        assert_eq!(*world.get::<Position>(entity).unwrap(), Position(3));
+    }
+
+    #[test]
+    fn added_and_changed_follow_trackers() {
+        let mut world = World::new();
+        let a = world.spawn((Position(0),));
+        let b = world.spawn((Position(0),));
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().count(), 2);
+        assert_eq!(world.query_filtered::<Entity, Changed<Position>>().count(), 2);
+
+        world.clear_trackers();
+        assert!(world.query_filtered::<Entity, Added<Position>>().is_empty());
+        assert!(world.query_filtered::<Entity, Changed<Position>>().is_empty());
+
+        world.get_mut::<Position>(a).unwrap().0 = 1;
+        let c = world.spawn((Position(0),));
+        let changed = world.query_filtered::<Entity, Changed<Position>>().iter().collect::<Vec<_>>();
+        assert_eq!(changed, vec![a, c]);
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().single(), Some(c));
+
+        // Substituir o componente é alteração, não adição
+        world.clear_trackers();
+        world.insert(b, (Position(9),));
+        assert_eq!(world.query_filtered::<Entity, Changed<Position>>().single(), Some(b));
+        assert!(world.query_filtered::<Entity, Added<Position>>().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed mutably")]
+    fn conflicting_borrows_in_one_query_panic() {
+        let mut world = World::new();
+        world.spawn((Position(0),));
+        let _query = world.query::<(&mut Position, &Position)>();
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn overlapping_queries_panic() {
+        let mut world = World::new();
+        world.spawn((Position(0),));
+        let _read = world.query::<&Position>();
+        let _write = world.query::<&mut Position>();
+    }
+}
@@ -0,0 +1,663 @@
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+
+use super::system::{Access, IntoSystem, System};
+use super::world::{Component, World};
+
+// Estágio criado pelo `Schedule::new`; sistemas sem `in_stage` vão para ele
+pub const DEFAULT_STAGE: &str = "update";
+
+type RunCondition = Box<dyn FnMut(&World) -> bool + Send>;
+
+// Sistema com as regras de agendamento: rótulos, ordem relativa, condições e estágio
+pub struct SystemConfig {
+    system: Box<dyn System>,
+    labels: Vec<String>,
+    before: Vec<String>,
+    after: Vec<String>,
+    conditions: Vec<RunCondition>,
+    stage: Option<String>,
+}
+
+impl SystemConfig {
+    pub fn new<M>(system: impl IntoSystem<M>) -> Self {
+        Self {
+            system: Box::new(system.into_system()),
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+            stage: None,
+        }
+    }
+
+    // Nome usado pelo `before`/`after` de outros sistemas; vários sistemas podem dividir um rótulo
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    // Roda antes dos sistemas com esse rótulo (no mesmo estágio)
+    pub fn before(mut self, label: impl Into<String>) -> Self {
+        self.before.push(label.into());
+        self
+    }
+
+    pub fn after(mut self, label: impl Into<String>) -> Self {
+        self.after.push(label.into());
+        self
+    }
+
+    // O sistema só roda se todas as condições forem verdadeiras (avaliadas uma vez por execução do estágio)
+    pub fn run_if(mut self, condition: impl FnMut(&World) -> bool + Send + 'static) -> Self {
+        self.conditions.push(Box::new(condition));
+        self
+    }
+
+    pub fn in_stage(mut self, stage: impl Into<String>) -> Self {
+        self.stage = Some(stage.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.system.name()
+    }
+}
+
+// Permite configurar direto a função: `schedule.add_system(mover.after("input"))`
+pub trait IntoSystemConfig<Marker>: Sized {
+    fn into_config(self) -> SystemConfig;
+
+    fn label(self, label: impl Into<String>) -> SystemConfig {
+        self.into_config().label(label)
+    }
+
+    fn before(self, label: impl Into<String>) -> SystemConfig {
+        self.into_config().before(label)
+    }
+
+    fn after(self, label: impl Into<String>) -> SystemConfig {
+        self.into_config().after(label)
+    }
+
+    fn run_if(self, condition: impl FnMut(&World) -> bool + Send + 'static) -> SystemConfig {
+        self.into_config().run_if(condition)
+    }
+
+    fn in_stage(self, stage: impl Into<String>) -> SystemConfig {
+        self.into_config().in_stage(stage)
+    }
+}
+
+impl<M, S: IntoSystem<M>> IntoSystemConfig<M> for S {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(self)
+    }
+}
+
+pub struct IsSystemConfig;
+
+impl IntoSystemConfig<IsSystemConfig> for SystemConfig {
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+// Condições prontas para o `run_if`
+pub fn resource_exists<R: Component>() -> impl FnMut(&World) -> bool + Send {
+    |world: &World| world.contains_resource::<R>()
+}
+
+pub fn resource_equals<R: Component + PartialEq>(value: R) -> impl FnMut(&World) -> bool + Send {
+    move |world: &World| world.resource::<R>().is_some_and(|resource| *resource == value)
+}
+
+pub fn run_once() -> impl FnMut(&World) -> bool + Send {
+    let mut done = false;
+    move |_: &World| !std::mem::replace(&mut done, true)
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ExecutorKind {
+    // Um sistema por vez, sempre na mesma ordem (testes, replays)
+    SingleThreaded,
+    // Sistemas sem conflito de acesso rodam juntos no pool do rayon
+    #[default]
+    MultiThreaded,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Status {
+    Waiting,
+    Running,
+    Done,
+}
+
+struct ScheduledSystem {
+    system: Option<Box<dyn System>>,  // Fora do slot enquanto roda em outra thread
+    name: String,
+    access: Access,
+    labels: Vec<String>,
+    before: Vec<String>,
+    after: Vec<String>,
+    conditions: Vec<RunCondition>,
+}
+
+impl ScheduledSystem {
+    fn is_exclusive(&self) -> bool {
+        self.access.is_exclusive()
+    }
+
+    fn system(&mut self) -> &mut Box<dyn System> {
+        self.system.as_mut().expect("system is running")
+    }
+}
+
+type Finished = (usize, Box<dyn System>, std::thread::Result<()>);
+
+// Grupo de sistemas entre dois pontos de sincronização. Os comandos de todos os sistemas
+// do estágio são aplicados no fim dele, na ordem em que os sistemas foram adicionados.
+struct Stage {
+    name: String,
+    systems: Vec<ScheduledSystem>,
+    initialized: usize,
+    dependencies: Option<Vec<Vec<usize>>>,  // Para cada sistema, os que precisam terminar antes
+}
+
+impl Stage {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            systems: Vec::new(),
+            initialized: 0,
+            dependencies: None,
+        }
+    }
+
+    fn add(&mut self, config: SystemConfig) {
+        self.systems.push(ScheduledSystem {
+            name: config.system.name().to_string(),
+            access: config.system.access().clone(),
+            system: Some(config.system),
+            labels: config.labels,
+            before: config.before,
+            after: config.after,
+            conditions: config.conditions,
+        });
+        self.dependencies = None;
+    }
+
+    // Monta o grafo de `before`/`after`; entra em pânico se houver ciclo ou se um rótulo não
+    // existir no estágio (entre estágios a ordem já é a dos estágios, e a regra seria ignorada)
+    fn build(&self, known_labels: &HashSet<&str>) -> Vec<Vec<usize>> {
+        let count = self.systems.len();
+        let mut dependencies = vec![Vec::new(); count];
+        let with_label = |label: &str| {
+            let systems: Vec<usize> = (0..count)
+                .filter(|&i| self.systems[i].labels.iter().any(|l| l == label))
+                .collect();
+            if systems.is_empty() && known_labels.contains(label) {
+                panic!("System label \"{}\" is not in stage \"{}\" (ordering only works within a stage)", label, self.name);
+            }
+            if systems.is_empty() {
+                panic!("System label \"{}\" does not exist", label);
+            }
+            systems
+        };
+        for (i, scheduled) in self.systems.iter().enumerate() {
+            for label in &scheduled.after {
+                dependencies[i].extend(with_label(label).into_iter().filter(|&j| j != i));
+            }
+            for label in &scheduled.before {
+                for j in with_label(label).into_iter().filter(|&j| j != i) {
+                    dependencies[j].push(i);
+                }
+            }
+        }
+
+        // Kahn: se sobrar sistema sem poder rodar, há um ciclo
+        let mut done = vec![false; count];
+        let mut remaining = count;
+        while remaining > 0 {
+            let ready: Vec<usize> = (0..count)
+                .filter(|&i| !done[i] && dependencies[i].iter().all(|&j| done[j]))
+                .collect();
+            if ready.is_empty() {
+                let cycle: Vec<&str> = (0..count)
+                    .filter(|&i| !done[i])
+                    .map(|i| self.systems[i].name.as_str())
+                    .collect();
+                panic!("Cycle in system ordering in stage \"{}\": {}", self.name, cycle.join(", "));
+            }
+            for i in ready {
+                done[i] = true;
+                remaining -= 1;
+            }
+        }
+        dependencies
+    }
+
+    fn run(&mut self, world: &mut World, executor: ExecutorKind, known_labels: &HashSet<&str>) {
+        for scheduled in &mut self.systems[self.initialized..] {
+            scheduled.system().initialize(world);
+        }
+        self.initialized = self.systems.len();
+        let dependencies = match self.dependencies.take() {
+            Some(dependencies) => dependencies,
+            None => self.build(known_labels),
+        };
+
+        let mut status = vec![Status::Waiting; self.systems.len()];
+        let mut should_run = vec![None; self.systems.len()];
+        world.flush();
+        loop {
+            let world_ref: &World = world;
+            let exclusive = match executor {
+                ExecutorKind::MultiThreaded => rayon::in_place_scope(|scope| {
+                    self.run_phase(world_ref, Some(scope), &dependencies, &mut status, &mut should_run)
+                }),
+                ExecutorKind::SingleThreaded => {
+                    self.run_phase(world_ref, None, &dependencies, &mut status, &mut should_run)
+                }
+            };
+            let Some(i) = exclusive else {
+                break;
+            };
+            self.systems[i].system().run_exclusive(world);
+            status[i] = Status::Done;
+        }
+
+        for (scheduled, ran) in self.systems.iter_mut().zip(should_run) {
+            if ran == Some(true) && !scheduled.is_exclusive() {
+                scheduled.system().apply_deferred(world);
+            }
+        }
+        self.dependencies = Some(dependencies);
+    }
+
+    // Roda os sistemas normais até acabarem ou até o próximo exclusivo pronto, que é devolvido
+    // para rodar com `&mut World`. Os sistemas são considerados na ordem de inserção: no modo
+    // single-threaded roda sempre o primeiro pronto; no paralelo, todos os prontos cujo acesso
+    // não conflita com os que já estão rodando.
+    fn run_phase<'s>(
+        &mut self,
+        world: &'s World,
+        scope: Option<&rayon::Scope<'s>>,
+        dependencies: &[Vec<usize>],
+        status: &mut [Status],
+        should_run: &mut [Option<bool>],
+    ) -> Option<usize> {
+        let (sender, receiver) = mpsc::channel::<Finished>();
+        let mut running: Vec<usize> = Vec::new();
+        loop {
+            let mut progressed = false;
+            let mut exclusive = None;
+            for i in 0..self.systems.len() {
+                if status[i] != Status::Waiting || !dependencies[i].iter().all(|&j| status[j] == Status::Done) {
+                    continue;
+                }
+                let scheduled = &mut self.systems[i];
+                let run = *should_run[i]
+                    .get_or_insert_with(|| scheduled.conditions.iter_mut().all(|condition| condition(world)));
+                if !run {
+                    status[i] = Status::Done;
+                    progressed = true;
+                    continue;
+                }
+                if scheduled.is_exclusive() {
+                    exclusive = Some(i);
+                    break;
+                }
+                if !running.iter().all(|&j| self.systems[j].access.is_compatible(&self.systems[i].access)) {
+                    continue;
+                }
+
+                let mut system = self.systems[i].system.take().expect("system is running");
+                match scope {
+                    Some(scope) => {
+                        let sender = sender.clone();
+                        scope.spawn(move |_| {
+                            // O pânico volta para a thread do `Schedule::run`, que está esperando o resultado
+                            let result = panic::catch_unwind(AssertUnwindSafe(|| system.run(world)));
+                            let _ = sender.send((i, system, result));
+                        });
+                        status[i] = Status::Running;
+                        running.push(i);
+                    }
+                    None => {
+                        system.run(world);
+                        self.systems[i].system = Some(system);
+                        status[i] = Status::Done;
+                        progressed = true;
+                        break;
+                    }
+                }
+            }
+
+            if progressed {
+                continue;
+            }
+            if running.is_empty() {
+                return exclusive;
+            }
+            let (i, system, result) = receiver.recv().expect("system thread disconnected");
+            self.systems[i].system = Some(system);
+            status[i] = Status::Done;
+            running.retain(|&j| j != i);
+            if let Err(payload) = result {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+// Estágios executados em sequência, cada um com seus sistemas. Dentro de um estágio, sistemas
+// cujos acessos (derivados dos parâmetros) não conflitam rodam em paralelo.
+//
+//     let mut schedule = Schedule::new();
+//     schedule
+//         .add_stage_before(DEFAULT_STAGE, "input")
+//         .add_system(ler_controles.in_stage("input"))
+//         .add_system(mover.label("movimento"))
+//         .add_system(colisoes.after("movimento").run_if(resource_exists::<Fisica>()));
+//     schedule.run(&mut world);
+pub struct Schedule {
+    stages: Vec<Stage>,
+    executor: ExecutorKind,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            stages: vec![Stage::new(DEFAULT_STAGE.to_string())],
+            executor: ExecutorKind::default(),
+        }
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn single_threaded() -> Self {
+        let mut schedule = Self::default();
+        schedule.set_executor(ExecutorKind::SingleThreaded);
+        schedule
+    }
+
+    pub fn set_executor(&mut self, executor: ExecutorKind) -> &mut Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn executor(&self) -> ExecutorKind {
+        self.executor
+    }
+
+    // Adiciona um estágio no fim
+    pub fn add_stage(&mut self, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        self.assert_new_stage(&name);
+        self.stages.push(Stage::new(name));
+        self
+    }
+
+    pub fn add_stage_before(&mut self, target: &str, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        self.assert_new_stage(&name);
+        let index = self.stage_index(target);
+        self.stages.insert(index, Stage::new(name));
+        self
+    }
+
+    pub fn add_stage_after(&mut self, target: &str, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        self.assert_new_stage(&name);
+        let index = self.stage_index(target);
+        self.stages.insert(index + 1, Stage::new(name));
+        self
+    }
+
+    pub fn has_stage(&self, name: &str) -> bool {
+        self.stages.iter().any(|stage| stage.name == name)
+    }
+
+    pub fn stage_names(&self) -> impl Iterator<Item = &str> {
+        self.stages.iter().map(|stage| stage.name.as_str())
+    }
+
+    fn stage_index(&self, name: &str) -> usize {
+        self.stages
+            .iter()
+            .position(|stage| stage.name == name)
+            .unwrap_or_else(|| panic!("Stage \"{}\" does not exist", name))
+    }
+
+    fn assert_new_stage(&self, name: &str) {
+        if self.has_stage(name) {
+            panic!("Stage \"{}\" already exists", name);
+        }
+    }
+
+    pub fn add_system<M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        let config = system.into_config();
+        let index = self.stage_index(config.stage.as_deref().unwrap_or(DEFAULT_STAGE));
+        self.stages[index].add(config);
+        self
+    }
+
+    pub fn system_count(&self) -> usize {
+        self.stages.iter().map(|stage| stage.systems.len()).sum()
+    }
+
+    pub fn system_names(&self) -> impl Iterator<Item = &str> {
+        self.stages
+            .iter()
+            .flat_map(|stage| stage.systems.iter().map(|scheduled| scheduled.name.as_str()))
+    }
+
+    pub fn run(&mut self, world: &mut World) {
+        let labels: Vec<String> = self
+            .stages
+            .iter()
+            .flat_map(|stage| stage.systems.iter().flat_map(|scheduled| scheduled.labels.iter().cloned()))
+            .collect();
+        let known_labels: HashSet<&str> = labels.iter().map(String::as_str).collect();
+        for stage in &mut self.stages {
+            stage.run(world, self.executor, &known_labels);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::ecs::commands::Commands;
+    use crate::ecs::entity::Entity;
+    use crate::ecs::query::Query;
+    use crate::ecs::world::ResMut;
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    fn logger(name: &'static str) -> impl FnMut(ResMut<Log>) + Send + 'static {
+        move |mut log: ResMut<Log>| log.0.push(name)
+    }
+
+    fn run(schedule: &mut Schedule) -> Vec<&'static str> {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        schedule.run(&mut world);
+        world.remove_resource::<Log>().unwrap().0
+    }
+
+    #[test]
+    fn before_and_after_override_insertion_order() {
+        for executor in [ExecutorKind::SingleThreaded, ExecutorKind::MultiThreaded] {
+            let mut schedule = Schedule::new();
+            schedule
+                .set_executor(executor)
+                .add_system(logger("c").after("b"))
+                .add_system(logger("b").label("b"))
+                .add_system(logger("a").before("b"));
+            let log = run(&mut schedule);
+            assert_eq!(log.len(), 3);
+            let position = |name| log.iter().position(|&entry| entry == name).unwrap();
+            assert!(position("a") < position("b"), "{:?}", log);
+            assert!(position("b") < position("c"), "{:?}", log);
+        }
+    }
+
+    #[test]
+    fn single_threaded_is_deterministic() {
+        let mut schedule = Schedule::single_threaded();
+        schedule
+            .add_system(logger("d").label("d"))
+            .add_system(logger("a"))
+            .add_system(logger("b"))
+            .add_system(logger("c").before("d"));
+        // Fora as restrições, vale a ordem de inserção
+        for _ in 0..10 {
+            assert_eq!(run(&mut schedule), vec!["a", "b", "c", "d"]);
+        }
+    }
+
+    #[test]
+    fn stages_run_in_order() {
+        let mut schedule = Schedule::single_threaded();
+        schedule
+            .add_stage_before(DEFAULT_STAGE, "input")
+            .add_stage("late")
+            .add_system(logger("late").in_stage("late"))
+            .add_system(logger("update"))
+            .add_system(logger("input").in_stage("input"));
+        assert_eq!(run(&mut schedule), vec!["input", "update", "late"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cycle in system ordering")]
+    fn cycles_panic() {
+        let mut schedule = Schedule::single_threaded();
+        schedule
+            .add_system(logger("a").label("a").after("b"))
+            .add_system(logger("b").label("b").after("a"));
+        run(&mut schedule);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn unknown_labels_panic() {
+        let mut schedule = Schedule::single_threaded();
+        schedule.add_system(logger("a").after("missing"));
+        run(&mut schedule);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not in stage")]
+    fn cross_stage_labels_panic() {
+        let mut schedule = Schedule::single_threaded();
+        schedule
+            .add_stage("late")
+            .add_system(logger("late").label("late").in_stage("late"))
+            .add_system(logger("update").before("late"));
+        run(&mut schedule);
+    }
+
+    #[test]
+    fn run_conditions() {
+        #[derive(PartialEq)]
+        struct Paused(bool);
+
+        let mut schedule = Schedule::single_threaded();
+        schedule
+            .add_system(logger("once").run_if(run_once()))
+            .add_system(logger("running").run_if(resource_equals(Paused(false))))
+            .add_system(logger("missing").run_if(resource_exists::<Paused>()));
+
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().unwrap().0, vec!["once"]);
+
+        world.insert_resource(Paused(false));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().unwrap().0, vec!["once", "running", "missing"]);
+
+        world.insert_resource(Paused(true));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().unwrap().0, vec!["once", "running", "missing", "missing"]);
+    }
+
+    // Registra se já existe alguma entidade
+    fn log_entities(query: Query<Entity>, mut log: ResMut<Log>) {
+        log.0.push(if query.is_empty() { "empty" } else { "spawned" });
+    }
+
+    #[test]
+    fn exclusive_systems_see_earlier_systems_and_apply_immediately() {
+        fn spawn_now(world: &mut World) {
+            world.get_resource_mut::<Log>().unwrap().0.push("exclusive");
+            world.spawn(());
+        }
+
+        for executor in [ExecutorKind::SingleThreaded, ExecutorKind::MultiThreaded] {
+            let mut schedule = Schedule::new();
+            schedule
+                .set_executor(executor)
+                .add_system(logger("before").label("before"))
+                .add_system(spawn_now.label("exclusive").after("before"))
+                .add_system(log_entities.after("exclusive"));
+            assert_eq!(run(&mut schedule), vec!["before", "exclusive", "spawned"]);
+        }
+    }
+
+    #[test]
+    fn conflicting_systems_never_overlap() {
+        // Todos escrevem no mesmo recurso: rodando juntos, o segundo `ResMut` entraria em pânico
+        #[derive(Default)]
+        struct Shared {
+            active: Arc<AtomicUsize>,
+            max: usize,
+        }
+
+        fn busy(mut shared: ResMut<Shared>) {
+            let active = shared.active.fetch_add(1, Ordering::SeqCst) + 1;
+            shared.max = shared.max.max(active);
+            std::thread::sleep(Duration::from_millis(5));
+            shared.active.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        let mut schedule = Schedule::new();
+        assert_eq!(schedule.executor(), ExecutorKind::MultiThreaded);
+        for _ in 0..4 {
+            schedule.add_system(busy);
+        }
+        let mut world = World::new();
+        world.insert_resource(Shared::default());
+        for _ in 0..3 {
+            schedule.run(&mut world);
+        }
+        assert_eq!(world.resource::<Shared>().unwrap().max, 1);
+    }
+
+    #[test]
+    fn commands_are_applied_at_the_end_of_the_stage() {
+        fn spawn_later(mut commands: Commands) {
+            commands.spawn(());
+        }
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_stage("late")
+            .add_system(spawn_later.label("spawn"))
+            .add_system(log_entities.after("spawn"))
+            .add_system(log_entities.in_stage("late"));
+        assert_eq!(run(&mut schedule), vec!["empty", "spawned"]);
+    }
+}
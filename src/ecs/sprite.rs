@@ -0,0 +1,132 @@
+use glam::Vec2;
+
+use super::query::Query;
+use super::transform::GlobalTransform;
+use super::world::{ResMut, World};
+use crate::graphics::atlas::AtlasRegion;
+use crate::graphics::material::MaterialId;
+use crate::graphics::render::{BlendMode, Render, RenderError};
+use crate::graphics::sprite::{Sprite as BatchSprite, SpriteBatch};
+use crate::graphics::texture::TextureHandle;
+
+// Sprite desenhado na posição do `GlobalTransform` da entidade. A escala do transform
+// multiplica `size`; o `anchor` diz qual ponto do quad fica na posição da entidade
+// (0,0 = centro, -0.5,-0.5 = canto inferior esquerdo, 0.5,0.5 = canto superior direito).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprite {
+    pub texture: TextureHandle,
+    pub size: Vec2,          // Tamanho em unidades do mundo (com zoom 1, pixels)
+    pub uv_rect: [f32; 4],   // Região da textura: x, y, largura, altura (0..1)
+    pub tint: [f32; 4],      // Cor multiplicada pela textura (RGBA)
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub anchor: Vec2,
+    pub layer: f32,          // Camadas maiores são desenhadas por cima
+    pub visible: bool,
+    pub blend: BlendMode,
+    pub material: Option<MaterialId>,  // `None` = shader padrão dos sprites
+}
+
+impl Sprite {
+    pub fn new(texture: TextureHandle, size: Vec2) -> Self {
+        Self {
+            texture,
+            size,
+            uv_rect: [0.0, 0.0, 1.0, 1.0],
+            tint: [1.0, 1.0, 1.0, 1.0],
+            flip_x: false,
+            flip_y: false,
+            anchor: Vec2::ZERO,
+            layer: 0.0,
+            visible: true,
+            blend: BlendMode::Alpha,
+            material: None,
+        }
+    }
+
+    // Textura inteira, no tamanho dela em pixels (1x1 se a textura não existir)
+    pub fn from_texture(render: &Render, texture: TextureHandle) -> Self {
+        let (width, height) = render.texture_size(texture).unwrap_or((1, 1));
+        Self::new(texture, Vec2::new(width as f32, height as f32))
+    }
+
+    // Região de um atlas, no tamanho dela em pixels
+    pub fn from_region(region: &AtlasRegion) -> Self {
+        Self {
+            uv_rect: region.uv_rect,
+            ..Self::new(region.texture, Vec2::new(region.width as f32, region.height as f32))
+        }
+    }
+
+    pub fn with_tint(mut self, tint: [f32; 4]) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_anchor(mut self, anchor: Vec2) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: f32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_material(mut self, material: impl Into<MaterialId>) -> Self {
+        self.material = Some(material.into());
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    // Quad pronto para o `SpriteBatch`: centro deslocado pelo anchor e escala negativa para espelhar
+    pub fn to_batch_sprite(&self, transform: &GlobalTransform) -> BatchSprite {
+        let flip = Vec2::new(
+            if self.flip_x { -1.0 } else { 1.0 },
+            if self.flip_y { -1.0 } else { 1.0 },
+        );
+        BatchSprite {
+            position: transform.transform_point(-self.anchor * self.size).into(),
+            rotation: transform.rotation(),
+            scale: (self.size * transform.scale() * flip).into(),
+            uv_rect: self.uv_rect,
+            tint: self.tint,
+            layer: self.layer,
+            blend: self.blend,
+        }
+    }
+}
+
+// Sistema de extração: copia os sprites visíveis para o recurso `SpriteBatch`, que o renderer
+// desenha de uma vez (ordenado por camada e textura em `SpriteBatch::prepare`).
+// Deve rodar depois do `propagate_transforms`.
+pub fn extract_sprites(sprites: Query<(&Sprite, &GlobalTransform)>, mut batch: ResMut<SpriteBatch>) {
+    batch.clear();
+    for (sprite, transform) in sprites.iter() {
+        if !sprite.visible {
+            continue;
+        }
+        match sprite.material {
+            Some(material) => batch.push_with_material(material, sprite.texture, sprite.to_batch_sprite(transform)),
+            None => batch.push(sprite.texture, sprite.to_batch_sprite(transform)),
+        }
+    }
+}
+
+// Desenha o `SpriteBatch` extraído do `World` (nada, se o recurso não existir)
+pub fn render_sprites(world: &World, render: &mut Render) -> Result<(), RenderError> {
+    match world.resource::<SpriteBatch>() {
+        Some(batch) => render.render_batch(&batch),
+        None => render.render_batch(&SpriteBatch::new()),
+    }
+}
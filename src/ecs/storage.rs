@@ -0,0 +1,222 @@
+use std::any::{type_name, Any};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+use super::entity::Entity;
+
+// Marca de tempo (tick do `World`) em que o componente foi adicionado e alterado por último
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+// Armazenamento de um tipo de componente: vetor denso (iteração rápida) e um índice
+// esparso por entidade (acesso O(1)). Remoções trocam o último elemento de lugar.
+//
+// A estrutura (quais entidades têm o componente) só muda com `&mut World`, então filtros como
+// `With`/`Changed` a leem sem lock; só os dados ficam num RwLock, emprestados pelas queries.
+pub struct SparseSet<T> {
+    sparse: Vec<u32>,  // Índice da entidade -> posição no vetor denso (u32::MAX = ausente)
+    entities: Vec<Entity>,
+    added: Vec<u64>,
+    changed: Vec<AtomicU64>,  // Atômico: o `Mut` marca a alteração com a storage emprestada só para leitura
+    data: RwLock<Vec<T>>,
+}
+
+const EMPTY: u32 = u32::MAX;
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            added: Vec::new(),
+            changed: Vec::new(),
+            data: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl<T> SparseSet<T> {
+    pub fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let dense = *self.sparse.get(entity.index() as usize)?;
+        if dense == EMPTY || self.entities[dense as usize] != entity {
+            return None;
+        }
+        Some(dense as usize)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn ticks(&self, dense: usize) -> ComponentTicks {
+        ComponentTicks {
+            added: self.added[dense],
+            changed: self.changed[dense].load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn changed_tick(&self, dense: usize) -> &AtomicU64 {
+        &self.changed[dense]
+    }
+
+    // Valor e tick de alteração juntos (campos disjuntos)
+    pub fn get_mut(&mut self, dense: usize) -> (&mut T, &AtomicU64) {
+        (&mut lock_mut(&mut self.data)[dense], &self.changed[dense])
+    }
+
+    // Empresta os dados; entra em pânico se outro acesso estiver escrevendo em `T`
+    pub fn read(&self) -> RwLockReadGuard<'_, Vec<T>> {
+        try_read(&self.data, type_name::<T>())
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Vec<T>> {
+        try_write(&self.data, type_name::<T>())
+    }
+
+    pub fn data_mut(&mut self) -> &mut Vec<T> {
+        lock_mut(&mut self.data)
+    }
+
+    // Substituir um componente existente conta como alteração, não como adição
+    pub fn insert(&mut self, entity: Entity, value: T, tick: u64) -> Option<T> {
+        if let Some(dense) = self.dense_index(entity) {
+            *self.changed[dense].get_mut() = tick;
+            return Some(std::mem::replace(&mut self.data_mut()[dense], value));
+        }
+
+        let index = entity.index() as usize;
+        if self.sparse.len() <= index {
+            self.sparse.resize(index + 1, EMPTY);
+        }
+        self.sparse[index] = self.entities.len() as u32;
+        self.entities.push(entity);
+        self.added.push(tick);
+        self.changed.push(AtomicU64::new(tick));
+        self.data_mut().push(value);
+        None
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let dense = self.dense_index(entity)?;
+        self.sparse[entity.index() as usize] = EMPTY;
+        let last = self.entities.len() - 1;
+        if dense != last {
+            let moved = self.entities[last];
+            self.sparse[moved.index() as usize] = dense as u32;
+        }
+        self.entities.swap_remove(dense);
+        self.added.swap_remove(dense);
+        self.changed.swap_remove(dense);
+        Some(self.data_mut().swap_remove(dense))
+    }
+}
+
+// Acesso sem tipo às storages do `World` (despawn remove a entidade de todas)
+pub(crate) trait ErasedStorage: Send + Sync {
+    fn remove_entity(&mut self, entity: Entity);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Send + Sync + 'static> ErasedStorage for SparseSet<T> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.remove(entity);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// Com `&mut` não há como outro sistema estar segurando o lock; um lock envenenado
+// (panic dentro de um sistema) ainda tem dados consistentes para o ECS
+pub(crate) fn lock_mut<T: ?Sized>(lock: &mut RwLock<T>) -> &mut T {
+    lock.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Empréstimos conflitantes (ex.: dois `&mut T` no mesmo sistema) são erro de programação
+pub(crate) fn try_read<'a, T: ?Sized>(lock: &'a RwLock<T>, name: &str) -> RwLockReadGuard<'a, T> {
+    match lock.try_read() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => panic!("{} is already borrowed mutably", name),
+    }
+}
+
+pub(crate) fn try_write<'a, T: ?Sized>(lock: &'a RwLock<T>, name: &str) -> RwLockWriteGuard<'a, T> {
+    match lock.try_write() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => panic!("{} is already borrowed", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::entity::Entities;
+
+    fn entities(count: usize) -> Vec<Entity> {
+        let mut entities = Entities::default();
+        (0..count).map(|_| entities.alloc()).collect()
+    }
+
+    #[test]
+    fn remove_keeps_moved_entity_reachable() {
+        let e = entities(3);
+        let mut set = SparseSet::default();
+        for (i, entity) in e.iter().enumerate() {
+            set.insert(*entity, i, 1);
+        }
+
+        assert_eq!(set.remove(e[0]), Some(0));
+        assert_eq!(set.remove(e[0]), None);
+        assert!(!set.contains(e[0]));
+        let dense = set.dense_index(e[2]).unwrap();
+        assert_eq!(set.read()[dense], 2);
+        assert_eq!(set.entities().len(), 2);
+    }
+
+    #[test]
+    fn replacing_counts_as_change_not_add() {
+        let e = entities(1);
+        let mut set = SparseSet::default();
+        assert_eq!(set.insert(e[0], "a", 1), None);
+        assert_eq!(set.insert(e[0], "b", 5), Some("a"));
+        assert_eq!(set.ticks(0), ComponentTicks { added: 1, changed: 5 });
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn write_while_reading_panics() {
+        let set = SparseSet::<u32>::default();
+        let _read = set.read();
+        let _write = set.write();
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed mutably")]
+    fn read_while_writing_panics() {
+        let set = SparseSet::<u32>::default();
+        let _write = set.write();
+        let _read = set.read();
+    }
+
+    #[test]
+    fn shared_reads_are_allowed() {
+        let set = SparseSet::<u32>::default();
+        let _a = set.read();
+        let _b = set.read();
+    }
+}
@@ -0,0 +1,481 @@
+use std::any::{type_name, TypeId};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use super::commands::{CommandQueue, Commands};
+use super::query::{Query, QueryData, QueryFilter, Ticks};
+use super::world::{Component, Res, ResMut, World};
+
+// O que um sistema lê e escreve. Derivado dos parâmetros; dois sistemas com acessos
+// compatíveis podem rodar ao mesmo tempo.
+#[derive(Clone, Debug, Default)]
+pub struct Access {
+    components_read: HashSet<TypeId>,
+    components_write: HashSet<TypeId>,
+    resources_read: HashSet<TypeId>,
+    resources_write: HashSet<TypeId>,
+    exclusive: bool,  // `&mut World`: conflita com tudo
+}
+
+impl Access {
+    pub fn read_component(&mut self, id: TypeId) {
+        self.components_read.insert(id);
+    }
+
+    pub fn write_component(&mut self, id: TypeId) {
+        self.components_write.insert(id);
+    }
+
+    pub fn read_resource(&mut self, id: TypeId) {
+        self.resources_read.insert(id);
+    }
+
+    pub fn write_resource(&mut self, id: TypeId) {
+        self.resources_write.insert(id);
+    }
+
+    pub fn set_exclusive(&mut self) {
+        self.exclusive = true;
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn extend(&mut self, other: &Access) {
+        self.components_read.extend(&other.components_read);
+        self.components_write.extend(&other.components_write);
+        self.resources_read.extend(&other.resources_read);
+        self.resources_write.extend(&other.resources_write);
+        self.exclusive |= other.exclusive;
+    }
+
+    // Compatíveis se nenhum dos dois escreve no que o outro lê ou escreve
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        if self.exclusive || other.exclusive {
+            return false;
+        }
+        let disjoint = |writes: &HashSet<TypeId>, read: &HashSet<TypeId>, write: &HashSet<TypeId>| {
+            writes.is_disjoint(read) && writes.is_disjoint(write)
+        };
+        disjoint(&self.components_write, &other.components_read, &other.components_write)
+            && disjoint(&other.components_write, &self.components_read, &self.components_write)
+            && disjoint(&self.resources_write, &other.resources_read, &other.resources_write)
+            && disjoint(&other.resources_write, &self.resources_read, &self.resources_write)
+    }
+}
+
+// Algo que um sistema pode receber como argumento: `Query`, `Res`, `ResMut`, `Commands` ou tuplas.
+// `State` vive com o sistema entre execuções (ex.: a fila dos `Commands`).
+pub trait SystemParam {
+    type State: Send + 'static;
+    type Item<'w, 's>;
+
+    fn init(world: &mut World) -> Self::State;
+    fn access(access: &mut Access);
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World, ticks: Ticks) -> Self::Item<'w, 's>;
+
+    // Chamado com `&mut World` depois do sistema (aplica comandos)
+    fn apply(_state: &mut Self::State, _world: &mut World) {}
+}
+
+pub type SystemParamItem<'w, 's, P> = <P as SystemParam>::Item<'w, 's>;
+
+impl<Q: QueryData + 'static, F: QueryFilter + 'static> SystemParam for Query<'_, Q, F> {
+    type State = ();
+    type Item<'w, 's> = Query<'w, Q, F>;
+
+    fn init(_world: &mut World) -> Self::State {}
+
+    fn access(access: &mut Access) {
+        Q::access(access);
+        F::access(access);
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World, ticks: Ticks) -> Self::Item<'w, 's> {
+        Query::new(world, ticks)
+    }
+}
+
+impl<R: Component> SystemParam for Res<'_, R> {
+    type State = ();
+    type Item<'w, 's> = Res<'w, R>;
+
+    fn init(_world: &mut World) -> Self::State {}
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<R>());
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World, _ticks: Ticks) -> Self::Item<'w, 's> {
+        world
+            .resource::<R>()
+            .unwrap_or_else(|| panic!("Resource {} does not exist", type_name::<R>()))
+    }
+}
+
+impl<R: Component> SystemParam for ResMut<'_, R> {
+    type State = ();
+    type Item<'w, 's> = ResMut<'w, R>;
+
+    fn init(_world: &mut World) -> Self::State {}
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<R>());
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World, _ticks: Ticks) -> Self::Item<'w, 's> {
+        world
+            .resource_mut::<R>()
+            .unwrap_or_else(|| panic!("Resource {} does not exist", type_name::<R>()))
+    }
+}
+
+impl<R: Component> SystemParam for Option<Res<'_, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<Res<'w, R>>;
+
+    fn init(_world: &mut World) -> Self::State {}
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<R>());
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World, _ticks: Ticks) -> Self::Item<'w, 's> {
+        world.resource::<R>()
+    }
+}
+
+impl<R: Component> SystemParam for Option<ResMut<'_, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<ResMut<'w, R>>;
+
+    fn init(_world: &mut World) -> Self::State {}
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<R>());
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World, _ticks: Ticks) -> Self::Item<'w, 's> {
+        world.resource_mut::<R>()
+    }
+}
+
+impl SystemParam for Commands<'_, '_> {
+    type State = CommandQueue;
+    type Item<'w, 's> = Commands<'w, 's>;
+
+    fn init(_world: &mut World) -> Self::State {
+        CommandQueue::default()
+    }
+
+    fn access(_access: &mut Access) {}
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World, _ticks: Ticks) -> Self::Item<'w, 's> {
+        Commands::new(state, world)
+    }
+
+    fn apply(state: &mut Self::State, world: &mut World) {
+        state.apply(world);
+    }
+}
+
+macro_rules! impl_system_param_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        impl<$($name: SystemParam),*> SystemParam for ($($name,)*) {
+            type State = ($($name::State,)*);
+            type Item<'w, 's> = ($($name::Item<'w, 's>,)*);
+
+            fn init(world: &mut World) -> Self::State {
+                ($($name::init(world),)*)
+            }
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World, ticks: Ticks) -> Self::Item<'w, 's> {
+                let ($($name,)*) = state;
+                ($($name::fetch($name, world, ticks),)*)
+            }
+
+            fn apply(state: &mut Self::State, world: &mut World) {
+                let ($($name,)*) = state;
+                $($name::apply($name, world);)*
+            }
+        }
+    };
+}
+
+impl_system_param_tuple!();
+impl_system_param_tuple!(A);
+impl_system_param_tuple!(A, B);
+impl_system_param_tuple!(A, B, C);
+impl_system_param_tuple!(A, B, C, D);
+impl_system_param_tuple!(A, B, C, D, E);
+impl_system_param_tuple!(A, B, C, D, E, F);
+impl_system_param_tuple!(A, B, C, D, E, F, G);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H);
+
+// Um passo da lógica do jogo. Sistemas normais rodam com `&World` (os parâmetros emprestam
+// só o que declaram no `access`); os exclusivos recebem `&mut World`.
+pub trait System: Send + 'static {
+    fn name(&self) -> &str;
+    fn initialize(&mut self, world: &mut World);
+    fn access(&self) -> &Access;
+
+    fn is_exclusive(&self) -> bool {
+        self.access().is_exclusive()
+    }
+
+    // Não aplica os comandos; ver `apply_deferred`
+    fn run(&mut self, world: &World);
+    fn apply_deferred(&mut self, world: &mut World);
+
+    fn run_exclusive(&mut self, world: &mut World) {
+        self.run(world);
+        self.apply_deferred(world);
+    }
+}
+
+// Conversão de funções (e sistemas prontos) em `System`. O `Marker` só existe para separar as
+// implementações para cada assinatura.
+pub trait IntoSystem<Marker> {
+    type System: System;
+
+    fn into_system(self) -> Self::System;
+}
+
+impl<S: System> IntoSystem<()> for S {
+    type System = S;
+
+    fn into_system(self) -> S {
+        self
+    }
+}
+
+// Funções cujos argumentos são todos `SystemParam`:
+//
+//     fn mover(mut query: Query<(&mut Position, &Velocity)>, time: Res<Time>) { ... }
+pub trait SystemParamFunction<Marker>: Send + 'static {
+    type Param: SystemParam;
+
+    fn run(&mut self, param: SystemParamItem<'_, '_, Self::Param>);
+}
+
+macro_rules! impl_system_function {
+    ($($param:ident),*) => {
+        #[allow(non_snake_case, clippy::unused_unit)]
+        impl<Func, $($param: SystemParam),*> SystemParamFunction<fn($($param,)*)> for Func
+        where
+            Func: Send + 'static,
+            for<'a> &'a mut Func: FnMut($($param),*) + FnMut($(SystemParamItem<$param>),*),
+        {
+            type Param = ($($param,)*);
+
+            fn run(&mut self, param: SystemParamItem<'_, '_, ($($param,)*)>) {
+                // Chamada através de uma função genérica para o compilador escolher o `FnMut` com os itens
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<$($param),*>(mut f: impl FnMut($($param),*), $($param: $param),*) {
+                    f($($param),*)
+                }
+                let ($($param,)*) = param;
+                call_inner(self, $($param),*)
+            }
+        }
+    };
+}
+
+impl_system_function!();
+impl_system_function!(A);
+impl_system_function!(A, B);
+impl_system_function!(A, B, C);
+impl_system_function!(A, B, C, D);
+impl_system_function!(A, B, C, D, E);
+impl_system_function!(A, B, C, D, E, F);
+impl_system_function!(A, B, C, D, E, F, G);
+impl_system_function!(A, B, C, D, E, F, G, H);
+
+pub struct FunctionSystem<Marker, F: SystemParamFunction<Marker>> {
+    func: F,
+    state: Option<<F::Param as SystemParam>::State>,
+    access: Access,
+    last_run: u64,
+    marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> System for FunctionSystem<Marker, F> {
+    fn name(&self) -> &str {
+        type_name::<F>()
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if self.state.is_none() {
+            self.state = Some(F::Param::init(world));
+        }
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn run(&mut self, world: &World) {
+        let this_run = world.increment_change_tick();
+        let ticks = Ticks {
+            last_run: self.last_run,
+            this_run,
+        };
+        let state = self
+            .state
+            .as_mut()
+            .unwrap_or_else(|| panic!("System {} was not initialized", type_name::<F>()));
+        self.func.run(F::Param::fetch(state, world, ticks));
+        self.last_run = this_run;
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        if let Some(state) = &mut self.state {
+            F::Param::apply(state, world);
+        }
+    }
+}
+
+pub struct IsFunctionSystem;
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> IntoSystem<(IsFunctionSystem, Marker)> for F {
+    type System = FunctionSystem<Marker, F>;
+
+    fn into_system(self) -> Self::System {
+        let mut access = Access::default();
+        F::Param::access(&mut access);
+        FunctionSystem {
+            func: self,
+            state: None,
+            access,
+            last_run: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+// Sistema com acesso total: `fn setup(world: &mut World) { ... }`
+pub struct ExclusiveSystem<F> {
+    func: F,
+    access: Access,
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> System for ExclusiveSystem<F> {
+    fn name(&self) -> &str {
+        type_name::<F>()
+    }
+
+    fn initialize(&mut self, _world: &mut World) {}
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn run(&mut self, _world: &World) {
+        panic!("Exclusive system {} needs &mut World", type_name::<F>());
+    }
+
+    fn apply_deferred(&mut self, _world: &mut World) {}
+
+    fn run_exclusive(&mut self, world: &mut World) {
+        (self.func)(world);
+    }
+}
+
+pub struct IsExclusiveSystem;
+
+impl<F: FnMut(&mut World) + Send + 'static> IntoSystem<IsExclusiveSystem> for F {
+    type System = ExclusiveSystem<F>;
+
+    fn into_system(self) -> Self::System {
+        let mut access = Access::default();
+        access.set_exclusive();
+        ExclusiveSystem { func: self, access }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::entity::Entity;
+    use crate::ecs::query::Changed;
+
+    struct Position(i32);
+
+    #[derive(Default)]
+    struct Seen(usize);
+
+    fn access_of<Marker>(system: impl IntoSystem<Marker>) -> Access {
+        system.into_system().access().clone()
+    }
+
+    fn read_position(_query: Query<&Position>) {}
+    fn write_position(_query: Query<&mut Position>) {}
+    fn read_seen(_seen: Res<Seen>) {}
+    fn write_seen(_seen: ResMut<Seen>) {}
+
+    #[test]
+    fn access_is_derived_from_params() {
+        assert!(access_of(read_position).is_compatible(&access_of(read_position)));
+        assert!(!access_of(read_position).is_compatible(&access_of(write_position)));
+        assert!(!access_of(write_position).is_compatible(&access_of(write_position)));
+        assert!(access_of(write_position).is_compatible(&access_of(write_seen)));
+        assert!(!access_of(read_seen).is_compatible(&access_of(write_seen)));
+
+        let exclusive = access_of(|_world: &mut World| {});
+        assert!(exclusive.is_exclusive());
+        assert!(!exclusive.is_compatible(&access_of(read_seen)));
+    }
+
+    #[test]
+    fn changed_is_relative_to_the_last_run_of_each_system() {
+        fn count_changed(query: Query<Entity, Changed<Position>>, mut seen: ResMut<Seen>) {
+            seen.0 = query.count();
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        let entity = world.spawn((Position(0),));
+        world.spawn((Position(0),));
+
+        let mut system = count_changed.into_system();
+        system.initialize(&mut world);
+        system.run(&world);
+        assert_eq!(world.resource::<Seen>().unwrap().0, 2);
+        system.run(&world);
+        assert_eq!(world.resource::<Seen>().unwrap().0, 0);
+
+        world.get_mut::<Position>(entity).unwrap().0 = 1;
+        system.run(&world);
+        assert_eq!(world.resource::<Seen>().unwrap().0, 1);
+
+        // Outro sistema tem a sua própria referência
+        let mut other = count_changed.into_system();
+        other.initialize(&mut world);
+        other.run(&world);
+        assert_eq!(world.resource::<Seen>().unwrap().0, 2);
+    }
+
+    #[test]
+    fn commands_wait_for_apply_deferred() {
+        fn spawner(mut commands: Commands) {
+            commands.spawn((Position(0),));
+        }
+
+        let mut world = World::new();
+        let mut system = spawner.into_system();
+        system.initialize(&mut world);
+        system.run(&world);
+        assert_eq!(world.entity_count(), 0);
+        system.apply_deferred(&mut world);
+        assert_eq!(world.entity_count(), 1);
+
+        system.run_exclusive(&mut world);
+        assert_eq!(world.entity_count(), 2);
+    }
+}
@@ -0,0 +1,154 @@
+use glam::{Affine2, Mat4, Vec2, Vec3};
+
+use super::entity::Entity;
+use super::hierarchy::{Children, Parent};
+use super::query::{Added, Changed, Query, With, Without};
+
+// Posição, rotação e escala de uma entidade relativas ao pai (ou ao mundo, se não tiver pai)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D {
+    pub translation: Vec2,
+    pub rotation: f32,  // Radianos, anti-horário
+    pub scale: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        translation: Vec2::ZERO,
+        rotation: 0.0,
+        scale: Vec2::ONE,
+    };
+
+    pub fn from_xy(x: f32, y: f32) -> Self {
+        Self::from_translation(Vec2::new(x, y))
+    }
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec2) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    // Os componentes de transform de uma entidade: `world.spawn(Transform2D::from_xy(10.0, 0.0).bundle())`
+    pub fn bundle(self) -> (Transform2D, GlobalTransform) {
+        (self, GlobalTransform::default())
+    }
+
+    pub fn translate(&mut self, delta: Vec2) {
+        self.translation += delta;
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.rotation += angle;
+    }
+
+    pub fn compute_affine(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+// Transform final no mundo, calculado pelo `propagate_transforms` (não alterar à mão)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlobalTransform(Affine2);
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Self(Affine2::IDENTITY)
+    }
+}
+
+impl From<Transform2D> for GlobalTransform {
+    fn from(transform: Transform2D) -> Self {
+        Self(transform.compute_affine())
+    }
+}
+
+impl GlobalTransform {
+    pub fn affine(&self) -> Affine2 {
+        self.0
+    }
+
+    pub fn translation(&self) -> Vec2 {
+        self.0.translation
+    }
+
+    // Rotação e escala extraídas da matriz (com escala não uniforme num pai rotacionado, aproximadas)
+    pub fn rotation(&self) -> f32 {
+        self.0.to_scale_angle_translation().1
+    }
+
+    pub fn scale(&self) -> Vec2 {
+        self.0.to_scale_angle_translation().0
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.0.transform_point2(point)
+    }
+
+    pub fn mul_transform(&self, transform: &Transform2D) -> Self {
+        Self(self.0 * transform.compute_affine())
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        let matrix = self.0.matrix2;
+        Mat4::from_cols(
+            matrix.x_axis.extend(0.0).extend(0.0),
+            matrix.y_axis.extend(0.0).extend(0.0),
+            Vec3::Z.extend(0.0),
+            self.0.translation.extend(0.0).extend(1.0),
+        )
+    }
+}
+
+// Calcula o `GlobalTransform` de cada entidade a partir da raiz da hierarquia. Só recalcula
+// as subárvores "sujas": transform local alterado, pai trocado ou `GlobalTransform` recém-adicionado.
+// Deve rodar depois dos sistemas que movem entidades e antes de desenhar.
+pub fn propagate_transforms(
+    roots: Query<Entity, (With<Transform2D>, Without<Parent>)>,
+    transforms: Query<(&Transform2D, Option<&Children>)>,
+    changed_transforms: Query<Entity, Changed<Transform2D>>,
+    changed_parents: Query<Entity, Changed<Parent>>,
+    added_globals: Query<Entity, Added<GlobalTransform>>,
+    mut globals: Query<&mut GlobalTransform>,
+) {
+    let is_dirty = |entity: Entity| {
+        changed_transforms.contains(entity) || changed_parents.contains(entity) || added_globals.contains(entity)
+    };
+
+    // (entidade, transform global do pai, pai foi recalculado)
+    let mut pending: Vec<(Entity, Affine2, bool)> = roots.iter().map(|root| (root, Affine2::IDENTITY, false)).collect();
+    while let Some((entity, parent, parent_dirty)) = pending.pop() {
+        let Some((transform, children)) = transforms.get(entity) else {
+            continue;
+        };
+        let dirty = parent_dirty || is_dirty(entity);
+        let global = match globals.get_mut(entity) {
+            Some(mut global) if dirty => {
+                global.0 = parent * transform.compute_affine();
+                global.0
+            }
+            Some(global) => global.0,
+            None => parent * transform.compute_affine(),
+        };
+        if let Some(children) = children {
+            pending.extend(children.iter().map(|child| (child, global, dirty)));
+        }
+    }
+}
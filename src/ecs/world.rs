@@ -0,0 +1,400 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use super::entity::{Entities, Entity};
+use super::hierarchy::detach;
+use super::query::{Query, QueryData, QueryFilter, Ticks};
+use super::storage::{lock_mut, try_read, try_write, ComponentTicks, ErasedStorage, SparseSet};
+
+// Qualquer tipo `Send + Sync + 'static` pode ser um componente (ou um recurso)
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
+
+// Conjunto de componentes inseridos juntos: `world.spawn((Posicao(..), Velocidade(..)))`
+pub trait Bundle: Send + Sync + 'static {
+    fn insert_into(self, world: &mut World, entity: Entity);
+}
+
+macro_rules! impl_bundle {
+    ($($name:ident),*) => {
+        impl<$($name: Component),*> Bundle for ($($name,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn insert_into(self, world: &mut World, entity: Entity) {
+                let ($($name,)*) = self;
+                $(world.insert_one(entity, $name);)*
+            }
+        }
+    };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
+impl_bundle!(A, B, C, D, E, F, G, H, I);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+type Resource = RwLock<Box<dyn Any + Send + Sync>>;
+
+// Entidades, componentes e recursos. Cada tipo de componente tem sua storage (sparse set);
+// recursos são valores únicos por tipo (tempo, entrada, configurações).
+pub struct World {
+    entities: Entities,
+    storages: HashMap<TypeId, Box<dyn ErasedStorage>>,
+    resources: HashMap<TypeId, Resource>,
+    change_tick: AtomicU64,
+    last_change_tick: u64,  // Referência do `Changed`/`Added` nas queries feitas direto no `World`
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self {
+            entities: Entities::default(),
+            storages: HashMap::new(),
+            resources: HashMap::new(),
+            change_tick: AtomicU64::new(1),
+            last_change_tick: 0,
+        }
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        let entity = self.entities.alloc();
+        bundle.insert_into(self, entity);
+        entity
+    }
+
+    // Remove a entidade e todos os seus componentes; retorna `false` se ela já não existia.
+    // Ela sai dos `Children` do pai e os filhos dela viram raízes.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        self.entities.flush();
+        if !self.entities.is_alive(entity) {
+            return false;
+        }
+        detach(self, entity);
+        self.entities.free(entity);
+        for storage in self.storages.values_mut() {
+            storage.remove_entity(entity);
+        }
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter()
+    }
+
+    // Adiciona (ou substitui) componentes; retorna `false` se a entidade não existe
+    pub fn insert(&mut self, entity: Entity, bundle: impl Bundle) -> bool {
+        self.entities.flush();
+        if !self.entities.is_alive(entity) {
+            return false;
+        }
+        bundle.insert_into(self, entity);
+        true
+    }
+
+    fn insert_one<T: Component>(&mut self, entity: Entity, component: T) {
+        let tick = self.change_tick();
+        self.storage_mut::<T>().insert(entity, component, tick);
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        let storage = self.storages.get_mut(&TypeId::of::<T>())?;
+        storage.as_any_mut().downcast_mut::<SparseSet<T>>()?.remove(entity)
+    }
+
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.storage::<T>().is_some_and(|storage| storage.contains(entity))
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        let storage = self.storage::<T>()?;
+        let index = storage.dense_index(entity)?;
+        Some(Ref {
+            data: storage.read(),
+            index,
+        })
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
+        let tick = self.change_tick();
+        let storage = self.storages.get_mut(&TypeId::of::<T>())?;
+        let storage = storage.as_any_mut().downcast_mut::<SparseSet<T>>()?;
+        let index = storage.dense_index(entity)?;
+        let added = storage.ticks(index).added;
+        let (value, changed) = storage.get_mut(index);
+        Some(Mut { value, changed, added, tick })
+    }
+
+    // Query direta no `World`. `Changed`/`Added` comparam com o último `clear_trackers`.
+    //
+    //     for (entity, mut position, velocity) in world.query::<(Entity, &mut Position, &Velocity)>().iter_mut() { ... }
+    pub fn query<Q: QueryData>(&self) -> Query<'_, Q, ()> {
+        self.query_filtered::<Q, ()>()
+    }
+
+    pub fn query_filtered<Q: QueryData, F: QueryFilter>(&self) -> Query<'_, Q, F> {
+        let ticks = Ticks {
+            last_run: self.last_change_tick,
+            this_run: self.change_tick(),
+        };
+        Query::new(self, ticks)
+    }
+
+    // Encerra o "frame" das queries diretas: mudanças até aqui deixam de contar como `Changed`
+    pub fn clear_trackers(&mut self) {
+        self.last_change_tick = self.increment_change_tick();
+    }
+
+    pub fn insert_resource<R: Component>(&mut self, resource: R) -> Option<R> {
+        let previous = self.resources.insert(TypeId::of::<R>(), RwLock::new(Box::new(resource)))?;
+        let previous = previous.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        previous.downcast::<R>().ok().map(|boxed| *boxed)
+    }
+
+    pub fn remove_resource<R: Component>(&mut self) -> Option<R> {
+        let resource = self.resources.remove(&TypeId::of::<R>())?;
+        let resource = resource.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        resource.downcast::<R>().ok().map(|boxed| *boxed)
+    }
+
+    pub fn contains_resource<R: Component>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    // Empresta um recurso; entra em pânico se ele já estiver emprestado para escrita
+    pub fn resource<R: Component>(&self) -> Option<Res<'_, R>> {
+        let lock = self.resources.get(&TypeId::of::<R>())?;
+        Some(Res {
+            guard: try_read(lock, type_name::<R>()),
+            marker: PhantomData,
+        })
+    }
+
+    pub fn resource_mut<R: Component>(&self) -> Option<ResMut<'_, R>> {
+        let lock = self.resources.get(&TypeId::of::<R>())?;
+        Some(ResMut {
+            guard: try_write(lock, type_name::<R>()),
+            marker: PhantomData,
+        })
+    }
+
+    // Atalho com `&mut`: não passa pelo lock
+    pub fn get_resource_mut<R: Component>(&mut self) -> Option<&mut R> {
+        lock_mut(self.resources.get_mut(&TypeId::of::<R>())?).downcast_mut::<R>()
+    }
+
+    // Tick atual; componentes alterados agora recebem esse valor
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Acquire)
+    }
+
+    // Avança o tick e devolve o anterior (o tick "desta execução" de um sistema)
+    pub(crate) fn increment_change_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, Ordering::AcqRel)
+    }
+
+    // Torna vivas as entidades reservadas pelos `Commands`
+    pub fn flush(&mut self) {
+        self.entities.flush();
+    }
+
+    pub(crate) fn entity_allocator(&self) -> &Entities {
+        &self.entities
+    }
+
+    // Storage de `T`; `None` se nenhum `T` foi inserido ainda
+    pub(crate) fn storage<T: Component>(&self) -> Option<&SparseSet<T>> {
+        self.storages.get(&TypeId::of::<T>())?.as_any().downcast_ref::<SparseSet<T>>()
+    }
+
+    fn storage_mut<T: Component>(&mut self) -> &mut SparseSet<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(SparseSet::<T>::default()))
+            .as_any_mut()
+            .downcast_mut::<SparseSet<T>>()
+            .expect("storage type mismatch")
+    }
+}
+
+// Componente emprestado para leitura (`World::get`)
+pub struct Ref<'w, T> {
+    data: RwLockReadGuard<'w, Vec<T>>,
+    index: usize,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data[self.index]
+    }
+}
+
+// Componente emprestado para escrita: marca o componente como alterado ao ser modificado
+pub struct Mut<'a, T> {
+    pub(crate) value: &'a mut T,
+    pub(crate) changed: &'a AtomicU64,
+    pub(crate) added: u64,
+    pub(crate) tick: u64,
+}
+
+impl<T> Mut<'_, T> {
+    // Modifica sem disparar `Changed`
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+
+    // Marca como alterado sem modificar (ex.: para forçar a propagação de um transform)
+    pub fn set_changed(&mut self) {
+        self.changed.store(self.tick, Ordering::Relaxed);
+    }
+
+    pub fn ticks(&self) -> ComponentTicks {
+        ComponentTicks {
+            added: self.added,
+            changed: self.changed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.set_changed();
+        self.value
+    }
+}
+
+// Recurso emprestado para leitura
+pub struct Res<'w, R> {
+    guard: RwLockReadGuard<'w, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<R>,
+}
+
+impl<R: 'static> Deref for Res<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().expect("resource type mismatch")
+    }
+}
+
+// Recurso emprestado para escrita
+pub struct ResMut<'w, R> {
+    guard: RwLockWriteGuard<'w, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<R>,
+}
+
+impl<R: 'static> Deref for ResMut<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().expect("resource type mismatch")
+    }
+}
+
+impl<R: 'static> DerefMut for ResMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.guard.downcast_mut::<R>().expect("resource type mismatch")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    #[test]
+    fn despawn_removes_components_and_invalidates_id() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Velocity(2)));
+        assert_eq!(*world.get::<Position>(entity).unwrap(), Position(1));
+
+        assert!(world.despawn(entity));
+        assert!(!world.despawn(entity));
+        assert!(!world.is_alive(entity));
+        assert!(world.get::<Position>(entity).is_none());
+        assert!(!world.insert(entity, (Position(3),)));
+
+        // O slot é reaproveitado, mas o id antigo continua inválido
+        let reused = world.spawn((Position(4),));
+        assert_eq!(reused.index(), entity.index());
+        assert!(world.get::<Position>(entity).is_none());
+        assert!(!world.has::<Velocity>(reused));
+        assert_eq!(world.entity_count(), 1);
+    }
+
+    #[test]
+    fn get_mut_marks_changed_only_on_write() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(0),));
+        let added = world.get_mut::<Position>(entity).unwrap().ticks();
+        world.clear_trackers();
+
+        let mut position = world.get_mut::<Position>(entity).unwrap();
+        position.bypass_change_detection().0 = 1;
+        assert_eq!(position.ticks(), added);
+        position.0 = 2;
+        assert!(position.ticks().changed > added.changed);
+        assert_eq!(position.ticks().added, added.added);
+    }
+
+    #[test]
+    fn resources_are_replaced_and_removed() {
+        let mut world = World::new();
+        assert_eq!(world.insert_resource(Position(1)), None);
+        assert_eq!(world.insert_resource(Position(2)), Some(Position(1)));
+        world.resource_mut::<Position>().unwrap().0 += 1;
+        assert_eq!(*world.resource::<Position>().unwrap(), Position(3));
+        assert_eq!(world.remove_resource::<Position>(), Some(Position(3)));
+        assert!(!world.contains_resource::<Position>());
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn resource_mut_while_borrowed_panics() {
+        let mut world = World::new();
+        world.insert_resource(Position(0));
+        let _read = world.resource::<Position>();
+        let _write = world.resource_mut::<Position>();
+    }
+}
@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use super::atlas::AtlasRegion;
+use super::sprite::{Sprite, SpriteBatch};
+use super::texture::TextureHandle;
+
+// Menor duração aceita para um quadro, evita laços infinitos no `Animator::update`
+const MIN_FRAME_DURATION: f32 = 1.0 / 1000.0;
+
+// Um quadro da animação: qual parte de qual textura mostrar e por quanto tempo (segundos)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub texture: TextureHandle,
+    pub uv_rect: [f32; 4],
+    pub duration: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum PlayMode {
+    #[default]
+    Loop,      // Volta ao primeiro quadro depois do último
+    PingPong,  // Vai até o fim e volta, indefinidamente
+    Once,      // Para no último quadro
+}
+
+// Sequência de quadros com modo de reprodução e eventos nomeados em quadros específicos
+// (ex.: "passo" no quadro em que o pé toca o chão)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    mode: PlayMode,
+    events: Vec<(usize, String)>,
+}
+
+impl Animation {
+    // Erro se não houver quadros (dados de asset podem vir vazios)
+    pub fn new(frames: Vec<Frame>) -> Result<Self> {
+        if frames.is_empty() {
+            return Err(anyhow::anyhow!("Animation needs at least one frame"));
+        }
+        Ok(Self {
+            frames,
+            mode: PlayMode::default(),
+            events: Vec::new(),
+        })
+    }
+
+    // Cria a animação a partir de uma sprite sheet dividida em `columns` x `rows` células.
+    // `cells` são os índices das células, contados da esquerda para a direita e de cima para baixo.
+    // Erro com a grade vazia, célula fora da grade ou nenhuma célula.
+    pub fn from_grid(
+        texture: TextureHandle,
+        columns: u32,
+        rows: u32,
+        cells: impl IntoIterator<Item = u32>,
+        frame_duration: f32,
+    ) -> Result<Self> {
+        if columns == 0 || rows == 0 {
+            return Err(anyhow::anyhow!("Invalid animation grid: {}x{}", columns, rows));
+        }
+        let cell_width = 1.0 / columns as f32;
+        let cell_height = 1.0 / rows as f32;
+        let frames = cells
+            .into_iter()
+            .map(|cell| {
+                if cell / columns >= rows {
+                    return Err(anyhow::anyhow!("Cell {} is outside the {}x{} animation grid", cell, columns, rows));
+                }
+                Ok(Frame {
+                    texture,
+                    uv_rect: [
+                        (cell % columns) as f32 * cell_width,
+                        (cell / columns) as f32 * cell_height,
+                        cell_width,
+                        cell_height,
+                    ],
+                    duration: frame_duration,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(frames)
+    }
+
+    // Cria a animação a partir de regiões de um atlas, na ordem dada
+    pub fn from_regions<'a>(regions: impl IntoIterator<Item = &'a AtlasRegion>, frame_duration: f32) -> Result<Self> {
+        let frames = regions
+            .into_iter()
+            .map(|region| Frame {
+                texture: region.texture,
+                uv_rect: region.uv_rect,
+                duration: frame_duration,
+            })
+            .collect();
+        Self::new(frames)
+    }
+
+    pub fn with_mode(mut self, mode: PlayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // Dispara o evento `name` toda vez que a animação entra no quadro `frame`
+    pub fn with_event(mut self, frame: usize, name: impl Into<String>) -> Self {
+        debug_assert!(frame < self.frames.len(), "Animation event on frame {} of {}", frame, self.frames.len());
+        self.events.push((frame, name.into()));
+        self
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn mode(&self) -> PlayMode {
+        self.mode
+    }
+
+    pub fn total_duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    // Tempo até voltar ao mesmo quadro (e direção); `None` no modo Once, que não se repete
+    fn cycle_duration(&self) -> Option<f32> {
+        let duration = |frame: &Frame| frame.duration.max(MIN_FRAME_DURATION);
+        let total: f32 = self.frames.iter().map(duration).sum();
+        let first = duration(&self.frames[0]);
+        let last = duration(&self.frames[self.frames.len() - 1]);
+        match self.mode {
+            PlayMode::Loop => Some(total),
+            PlayMode::PingPong if self.frames.len() == 1 => Some(total),
+            // Ida e volta sem repetir as pontas: 0, 1, ..., último, ..., 1
+            PlayMode::PingPong => Some(2.0 * total - first - last),
+            PlayMode::Once => None,
+        }
+    }
+}
+
+// Estado de reprodução de uma animação; várias entidades podem compartilhar a mesma `Animation`
+#[derive(Clone, Debug)]
+pub struct Animator {
+    animation: Arc<Animation>,
+    current: usize,
+    elapsed: f32,   // Tempo já passado no quadro atual
+    forward: bool,  // Direção no modo PingPong
+    playing: bool,
+    finished: bool,
+    started: bool,  // O quadro inicial já teve os eventos disparados
+    pub speed: f32, // Multiplicador do tempo (1.0 = velocidade normal)
+    fired: Vec<String>,
+}
+
+impl Animator {
+    pub fn new(animation: Arc<Animation>) -> Self {
+        Self {
+            animation,
+            current: 0,
+            elapsed: 0.0,
+            forward: true,
+            playing: true,
+            finished: false,
+            started: false,
+            speed: 1.0,
+            fired: Vec::new(),
+        }
+    }
+
+    // Troca de animação; se já for a mesma, continua de onde estava
+    pub fn play(&mut self, animation: Arc<Animation>) {
+        if !Arc::ptr_eq(&self.animation, &animation) {
+            self.animation = animation;
+            self.reset();
+        }
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn resume(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.elapsed = 0.0;
+        self.forward = true;
+        self.finished = false;
+        self.started = false;
+    }
+
+    pub fn animation(&self) -> &Arc<Animation> {
+        &self.animation
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing && !self.finished
+    }
+
+    // Só faz sentido no modo Once: o último quadro já foi exibido pelo tempo todo
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current_frame(&self) -> &Frame {
+        &self.animation.frames[self.current]
+    }
+
+    pub fn texture(&self) -> TextureHandle {
+        self.current_frame().texture
+    }
+
+    pub fn uv_rect(&self) -> [f32; 4] {
+        self.current_frame().uv_rect
+    }
+
+    // Avança a animação em `dt` segundos e devolve os eventos disparados nesta atualização
+    pub fn update(&mut self, dt: f32) -> &[String] {
+        self.fired.clear();
+        if !self.started {
+            self.started = true;
+            self.fire_events();
+        }
+        if !self.playing || self.finished {
+            return &self.fired;
+        }
+
+        self.elapsed += dt * self.speed;
+        if !self.elapsed.is_finite() {
+            self.elapsed = 0.0;
+        }
+        // Ciclos inteiros terminam no mesmo quadro: são descartados antes de andar quadro a quadro,
+        // senão um `dt` grande (travada, `speed` alto) custaria um passo por quadro pulado e, além
+        // da precisão do f32, o `elapsed -= duration` deixaria de avançar. Os eventos dos ciclos
+        // descartados não são disparados.
+        if let Some(cycle) = self.animation.cycle_duration() {
+            if self.elapsed >= cycle {
+                self.elapsed %= cycle;
+            }
+        }
+        loop {
+            let duration = self.current_frame().duration.max(MIN_FRAME_DURATION);
+            if self.elapsed < duration {
+                break;
+            }
+            self.elapsed -= duration;
+            if !self.advance() {
+                self.finished = true;
+                self.elapsed = 0.0;
+                break;
+            }
+            self.fire_events();
+        }
+
+        &self.fired
+    }
+
+    // Vai para o próximo quadro conforme o modo; retorna `false` quando a animação termina
+    fn advance(&mut self) -> bool {
+        let last = self.animation.frames.len() - 1;
+        match self.animation.mode {
+            PlayMode::Loop => {
+                self.current = if self.current == last { 0 } else { self.current + 1 };
+            }
+            PlayMode::Once => {
+                if self.current == last {
+                    return false;
+                }
+                self.current += 1;
+            }
+            PlayMode::PingPong => {
+                if last == 0 {
+                    return true;
+                }
+                if self.forward && self.current == last {
+                    self.forward = false;
+                } else if !self.forward && self.current == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    self.current += 1;
+                } else {
+                    self.current -= 1;
+                }
+            }
+        }
+        true
+    }
+
+    fn fire_events(&mut self) {
+        let current = self.current;
+        self.fired.extend(
+            self.animation
+                .events
+                .iter()
+                .filter(|(frame, _)| *frame == current)
+                .map(|(_, name)| name.clone()),
+        );
+    }
+
+    // Copia o UV do quadro atual para o sprite
+    pub fn apply(&self, sprite: &mut Sprite) {
+        sprite.uv_rect = self.uv_rect();
+    }
+
+    // Adiciona o sprite ao batch já com a textura e o UV do quadro atual
+    pub fn draw(&self, batch: &mut SpriteBatch, mut sprite: Sprite) {
+        self.apply(&mut sprite);
+        batch.push(self.texture(), sprite);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Quatro quadros de 0.25s numa grade 4x1
+    fn animation(mode: PlayMode) -> Arc<Animation> {
+        let animation = Animation::from_grid(TextureHandle::for_test(0), 4, 1, 0..4, 0.25).unwrap();
+        Arc::new(animation.with_mode(mode).with_event(0, "start").with_event(2, "step"))
+    }
+
+    // Quadro exibido depois de cada `update(dt)`
+    fn sequence(animator: &mut Animator, dt: f32, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                animator.update(dt);
+                animator.frame_index()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grid_frames_map_cells_to_uvs() {
+        let animation = Animation::from_grid(TextureHandle::for_test(0), 2, 2, [3, 0], 0.1).unwrap();
+        assert_eq!(animation.frames()[0].uv_rect, [0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(animation.frames()[1].uv_rect, [0.0, 0.0, 0.5, 0.5]);
+        assert!(Animation::from_grid(TextureHandle::for_test(0), 2, 2, [4], 0.1).is_err());
+        assert!(Animation::from_grid(TextureHandle::for_test(0), 0, 2, [0], 0.1).is_err());
+        assert!(Animation::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let mut animator = Animator::new(animation(PlayMode::Loop));
+        assert_eq!(sequence(&mut animator, 0.25, 6), [1, 2, 3, 0, 1, 2]);
+        assert!(animator.is_playing());
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_both_ends() {
+        let mut animator = Animator::new(animation(PlayMode::PingPong));
+        assert_eq!(sequence(&mut animator, 0.25, 8), [1, 2, 3, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let mut animator = Animator::new(animation(PlayMode::Once));
+        assert_eq!(sequence(&mut animator, 0.25, 3), [1, 2, 3]);
+        assert!(!animator.is_finished());
+        animator.update(0.25);
+        assert!(animator.is_finished() && !animator.is_playing());
+        assert_eq!(animator.frame_index(), 3);
+        assert!(animator.update(10.0).is_empty());
+    }
+
+    #[test]
+    fn events_fire_when_entering_their_frame() {
+        let mut animator = Animator::new(animation(PlayMode::Loop));
+        assert_eq!(animator.update(0.0), ["start"]);
+        assert!(animator.update(0.3).is_empty());
+        assert_eq!(animator.update(0.25), ["step"]);
+        // Um update longo pode passar por vários quadros com eventos
+        assert_eq!(animator.update(0.5), ["start"]);
+        assert_eq!(animator.update(0.5), ["step"]);
+        assert_eq!(animator.frame_index(), 2);
+
+        animator.pause();
+        assert!(animator.update(1.0).is_empty());
+        assert_eq!(animator.frame_index(), 2);
+    }
+
+    #[test]
+    fn huge_deltas_skip_whole_cycles() {
+        for mode in [PlayMode::Loop, PlayMode::PingPong] {
+            let mut animator = Animator::new(animation(mode));
+            animator.speed = 1.0e9;
+            animator.update(1.0e3);
+            assert!(animator.frame_index() < 4);
+
+            // Ciclo de 1s no Loop e de 1.5s no PingPong: 10 ciclos e mais meio quadro
+            let mut animator = Animator::new(animation(mode));
+            let cycle = if mode == PlayMode::Loop { 1.0 } else { 1.5 };
+            animator.update(cycle * 10.0 + 0.6);
+            assert_eq!(animator.frame_index(), 2, "{:?}", mode);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::is_ron;
+
+use super::render::Render;
+use super::texture::TextureHandle;
+
+// Região de um atlas: retângulo em pixels dentro de uma página e o UV equivalente
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasRegion {
+    pub texture: TextureHandle,  // Textura da página onde a imagem está
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub uv_rect: [f32; 4],  // x, y, largura, altura em 0..1, pronto para `Sprite::uv_rect`
+}
+
+// Conjunto de páginas na GPU e as regiões nomeadas dentro delas
+pub struct Atlas {
+    pages: Vec<TextureHandle>,
+    regions: HashMap<String, AtlasRegion>,
+}
+
+impl Atlas {
+    pub fn get(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.get(name)
+    }
+
+    pub fn pages(&self) -> &[TextureHandle] {
+        &self.pages
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (&str, &AtlasRegion)> {
+        self.regions.iter().map(|(name, region)| (name.as_str(), region))
+    }
+
+    // Carrega um atlas pré-montado descrito por um manifesto JSON ou RON (pela extensão).
+    // Os caminhos das páginas são relativos à pasta do manifesto.
+    pub fn load_manifest(render: &mut Render, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao ler o manifesto {}: {}", path.display(), e))?;
+        let manifest = AtlasManifest::parse(&text, path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut pages = Vec::with_capacity(manifest.pages.len());
+        for page in &manifest.pages {
+            pages.push(render.load_texture(base_dir.join(page))?);
+        }
+
+        let mut regions = HashMap::with_capacity(manifest.regions.len());
+        for (name, rect) in manifest.regions {
+            let texture = *pages
+                .get(rect.page)
+                .ok_or_else(|| anyhow::anyhow!("Region '{}' references missing page {}", name, rect.page))?;
+            let (page_width, page_height) = render
+                .texture_size(texture)
+                .ok_or_else(|| anyhow::anyhow!("Atlas page {} is not loaded", rect.page))?;
+            let region = AtlasRegion {
+                texture,
+                page: rect.page,
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+                uv_rect: uv_rect(rect.x, rect.y, rect.width, rect.height, page_width, page_height),
+            };
+            regions.insert(name, region);
+        }
+
+        Ok(Self { pages, regions })
+    }
+}
+
+// Formato do manifesto de um atlas pré-montado
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AtlasManifest {
+    pub pages: Vec<String>,
+    pub regions: HashMap<String, ManifestRect>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestRect {
+    #[serde(default)]
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasManifest {
+    fn parse(text: &str, path: &Path) -> Result<Self> {
+        let manifest = if is_ron(path) {
+            ron::from_str(text).map_err(|e| anyhow::anyhow!("Manifesto RON inválido {}: {}", path.display(), e))?
+        } else {
+            serde_json::from_str(text).map_err(|e| anyhow::anyhow!("Manifesto JSON inválido {}: {}", path.display(), e))?
+        };
+        Ok(manifest)
+    }
+}
+
+// Resultado do empacotamento na CPU: as imagens das páginas e onde cada imagem ficou
+pub struct PackedAtlas {
+    pub pages: Vec<image::RgbaImage>,
+    pub regions: HashMap<String, ManifestRect>,
+}
+
+// Monta atlas em tempo de execução a partir de várias imagens.
+// As imagens são organizadas em prateleiras (shelf packing), da mais alta para a mais baixa,
+// abrindo uma nova página quando a atual enche.
+pub struct AtlasBuilder {
+    page_size: u32,
+    padding: u32,  // Espaço vazio entre as imagens e a borda da página
+    extrude: u32,  // Pixels de borda repetidos em volta de cada imagem (evita sangrar cor no filtro linear)
+    images: Vec<(String, image::RgbaImage)>,
+}
+
+impl AtlasBuilder {
+    pub fn new(page_size: u32) -> Self {
+        Self {
+            page_size,
+            padding: 1,
+            extrude: 0,
+            images: Vec::new(),
+        }
+    }
+
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn extrude(mut self, extrude: u32) -> Self {
+        self.extrude = extrude;
+        self
+    }
+
+    pub fn add(&mut self, name: impl Into<String>, img: image::RgbaImage) -> &mut Self {
+        self.images.push((name.into(), img));
+        self
+    }
+
+    // Adiciona uma imagem do disco, usando o caminho como nome da região
+    pub fn add_file(&mut self, path: impl AsRef<Path>) -> Result<&mut Self> {
+        let path = path.as_ref();
+        let img = image::open(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao carregar a imagem {}: {}", path.display(), e))?
+            .to_rgba8();
+        Ok(self.add(path.to_string_lossy(), img))
+    }
+
+    // Empacota as imagens sem tocar na GPU
+    pub fn pack(&self) -> Result<PackedAtlas> {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&i| {
+            let (_, img) = &self.images[i];
+            std::cmp::Reverse((img.height(), img.width()))
+        });
+
+        let mut pages = vec![image::RgbaImage::new(self.page_size, self.page_size)];
+        let mut regions = HashMap::with_capacity(self.images.len());
+        let (mut x, mut y, mut shelf_height) = (self.padding, self.padding, 0);
+
+        for index in order {
+            let (name, img) = &self.images[index];
+            if img.width() == 0 || img.height() == 0 {
+                return Err(anyhow::anyhow!("Image '{}' is empty", name));
+            }
+            let cell_width = img.width() + 2 * self.extrude;
+            let cell_height = img.height() + 2 * self.extrude;
+            if cell_width + 2 * self.padding > self.page_size || cell_height + 2 * self.padding > self.page_size {
+                return Err(anyhow::anyhow!(
+                    "Image '{}' ({}x{}) does not fit in a {}x{} atlas page",
+                    name, img.width(), img.height(), self.page_size, self.page_size
+                ));
+            }
+
+            // Nova prateleira quando a linha atual acabar
+            if x + cell_width + self.padding > self.page_size {
+                x = self.padding;
+                y += shelf_height + self.padding;
+                shelf_height = 0;
+            }
+            // Nova página quando não couber mais prateleira
+            if y + cell_height + self.padding > self.page_size {
+                pages.push(image::RgbaImage::new(self.page_size, self.page_size));
+                x = self.padding;
+                y = self.padding;
+                shelf_height = 0;
+            }
+
+            let page = pages.len() - 1;
+            blit_extruded(&mut pages[page], img, x, y, self.extrude);
+            let rect = ManifestRect {
+                page,
+                x: x + self.extrude,
+                y: y + self.extrude,
+                width: img.width(),
+                height: img.height(),
+            };
+            if regions.insert(name.clone(), rect).is_some() {
+                return Err(anyhow::anyhow!("Duplicated atlas region name '{}'", name));
+            }
+
+            x += cell_width + self.padding;
+            shelf_height = shelf_height.max(cell_height);
+        }
+
+        Ok(PackedAtlas { pages, regions })
+    }
+
+    // Empacota e envia as páginas para a GPU
+    pub fn build(&self, render: &mut Render) -> Result<Atlas> {
+        let packed = self.pack()?;
+
+        let pages: Vec<TextureHandle> = packed
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| render.create_texture(page, &format!("atlas_page_{}", i)))
+            .collect();
+
+        let regions = packed
+            .regions
+            .into_iter()
+            .map(|(name, rect)| {
+                let region = AtlasRegion {
+                    texture: pages[rect.page],
+                    page: rect.page,
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                    uv_rect: uv_rect(rect.x, rect.y, rect.width, rect.height, self.page_size, self.page_size),
+                };
+                (name, region)
+            })
+            .collect();
+
+        Ok(Atlas { pages, regions })
+    }
+}
+
+fn uv_rect(x: u32, y: u32, width: u32, height: u32, page_width: u32, page_height: u32) -> [f32; 4] {
+    [
+        x as f32 / page_width as f32,
+        y as f32 / page_height as f32,
+        width as f32 / page_width as f32,
+        height as f32 / page_height as f32,
+    ]
+}
+
+// Copia a imagem para (x, y) repetindo os pixels da borda `extrude` vezes para fora
+fn blit_extruded(page: &mut image::RgbaImage, img: &image::RgbaImage, x: u32, y: u32, extrude: u32) {
+    let (width, height) = img.dimensions();
+    for dy in 0..height + 2 * extrude {
+        for dx in 0..width + 2 * extrude {
+            let src_x = dx.saturating_sub(extrude).min(width - 1);
+            let src_y = dy.saturating_sub(extrude).min(height - 1);
+            page.put_pixel(x + dx, y + dy, *img.get_pixel(src_x, src_y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> image::RgbaImage {
+        image::RgbaImage::from_pixel(width, height, image::Rgba(color))
+    }
+
+    fn rect(packed: &PackedAtlas, name: &str) -> (usize, u32, u32, u32, u32) {
+        let rect = packed.regions[name];
+        (rect.page, rect.x, rect.y, rect.width, rect.height)
+    }
+
+    #[test]
+    fn shelves_are_filled_tallest_first_with_padding_and_extrude() {
+        let mut builder = AtlasBuilder::new(16).padding(1).extrude(1);
+        builder.add("b", solid(3, 2, [0, 255, 0, 255]));
+        builder.add("a", solid(4, 4, [255, 0, 0, 255]));
+        builder.add("c", solid(4, 3, [0, 0, 255, 255]));
+        let packed = builder.pack().unwrap();
+
+        assert_eq!(packed.pages.len(), 1);
+        assert_eq!(rect(&packed, "a"), (0, 2, 2, 4, 4));
+        assert_eq!(rect(&packed, "c"), (0, 9, 2, 4, 3));
+        // Não cabe mais na primeira prateleira (altura 6 + padding)
+        assert_eq!(rect(&packed, "b"), (0, 2, 9, 3, 2));
+
+        let page = &packed.pages[0];
+        assert_eq!(page.get_pixel(0, 0).0, [0, 0, 0, 0]);  // Padding
+        assert_eq!(page.get_pixel(1, 1).0, [255, 0, 0, 255]);  // Borda repetida em volta de "a"
+        assert_eq!(page.get_pixel(6, 3).0, [255, 0, 0, 255]);
+        assert_eq!(page.get_pixel(7, 3).0, [0, 0, 0, 0]);
+        assert_eq!(page.get_pixel(8, 1).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn full_pages_open_a_new_page() {
+        let mut builder = AtlasBuilder::new(8).padding(1);
+        builder.add("first", solid(6, 6, [255; 4]));
+        builder.add("second", solid(6, 6, [255; 4]));
+        let packed = builder.pack().unwrap();
+        assert_eq!(packed.pages.len(), 2);
+        let mut pages: Vec<_> = ["first", "second"].iter().map(|name| rect(&packed, name)).collect();
+        pages.sort();
+        assert_eq!(pages, [(0, 1, 1, 6, 6), (1, 1, 1, 6, 6)]);
+    }
+
+    #[test]
+    fn invalid_images_are_rejected() {
+        let mut too_big = AtlasBuilder::new(8).padding(1).extrude(1);
+        too_big.add("big", solid(5, 5, [255; 4]));
+        assert!(too_big.pack().is_err());
+
+        let mut empty = AtlasBuilder::new(8);
+        empty.add("empty", image::RgbaImage::new(0, 3));
+        assert!(empty.pack().is_err());
+
+        let mut duplicated = AtlasBuilder::new(8);
+        duplicated.add("same", solid(1, 1, [255; 4])).add("same", solid(2, 2, [255; 4]));
+        assert!(duplicated.pack().is_err());
+    }
+
+    #[test]
+    fn uv_rects_are_normalized_by_page_size() {
+        assert_eq!(uv_rect(8, 4, 16, 2, 32, 16), [0.25, 0.25, 0.5, 0.125]);
+    }
+}
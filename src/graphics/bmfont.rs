@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+// Glifo de uma fonte BMFont, em pixels da página
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BmChar {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub x_offset: i32,
+    pub y_offset: i32,  // Distância do topo da linha até o topo do glifo
+    pub x_advance: i32,
+    pub page: usize,
+}
+
+// Descrição de uma fonte bitmap no formato texto do BMFont (.fnt)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BmFontDescriptor {
+    pub size: f32,         // Tamanho em que a fonte foi gerada
+    pub line_height: f32,
+    pub base: f32,         // Distância do topo da linha até a linha de base
+    pub scale_w: u32,      // Tamanho das páginas
+    pub scale_h: u32,
+    pub pages: Vec<String>, // Arquivos das páginas, relativos ao .fnt
+    pub chars: HashMap<char, BmChar>,
+    pub kernings: HashMap<(char, char), i32>,
+}
+
+impl BmFontDescriptor {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut font = Self::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let mut tokens = tokenize(line).into_iter();
+            let Some((tag, _)) = tokens.next() else {
+                continue;
+            };
+            let attrs: HashMap<String, String> = tokens.collect();
+            let int = |key: &str| -> Result<i32> {
+                attrs
+                    .get(key)
+                    .ok_or_else(|| anyhow::anyhow!("Line {}: missing '{}' in '{}'", number + 1, key, tag))?
+                    .parse::<i32>()
+                    .map_err(|e| anyhow::anyhow!("Line {}: invalid '{}': {}", number + 1, key, e))
+            };
+
+            match tag.as_str() {
+                "info" => font.size = int("size")?.unsigned_abs() as f32,
+                "common" => {
+                    font.line_height = int("lineHeight")? as f32;
+                    font.base = int("base")? as f32;
+                    font.scale_w = int("scaleW")? as u32;
+                    font.scale_h = int("scaleH")? as u32;
+                }
+                "page" => {
+                    let id = int("id")? as usize;
+                    let file = attrs
+                        .get("file")
+                        .ok_or_else(|| anyhow::anyhow!("Line {}: page without file", number + 1))?;
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, String::new());
+                    }
+                    font.pages[id] = file.clone();
+                }
+                "char" => {
+                    let Some(ch) = char::from_u32(int("id")? as u32) else {
+                        continue;
+                    };
+                    font.chars.insert(
+                        ch,
+                        BmChar {
+                            x: int("x")? as u32,
+                            y: int("y")? as u32,
+                            width: int("width")? as u32,
+                            height: int("height")? as u32,
+                            x_offset: int("xoffset")?,
+                            y_offset: int("yoffset")?,
+                            x_advance: int("xadvance")?,
+                            page: int("page").unwrap_or(0) as usize,
+                        },
+                    );
+                }
+                "kerning" => {
+                    let first = char::from_u32(int("first")? as u32);
+                    let second = char::from_u32(int("second")? as u32);
+                    if let (Some(first), Some(second)) = (first, second) {
+                        font.kernings.insert((first, second), int("amount")?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if font.pages.is_empty() || font.scale_w == 0 || font.scale_h == 0 {
+            return Err(anyhow::anyhow!("BMFont file has no 'common' or 'page' entries"));
+        }
+        if font.size == 0.0 {
+            font.size = font.line_height;
+        }
+        Ok(font)
+    }
+}
+
+// Separa uma linha do .fnt em (chave, valor), respeitando valores entre aspas.
+// O primeiro token é o tipo da linha (info, common, page, char...) com valor vazio.
+fn tokenize(line: &str) -> Vec<(String, String)> {
+    let mut tokens = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            key.push(c);
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    value.push(c);
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+
+        if !key.is_empty() {
+            tokens.push((key, value));
+        }
+    }
+
+    tokens
+}
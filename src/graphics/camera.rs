@@ -0,0 +1,76 @@
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec2};
+
+// Câmera ortográfica 2D. Com zoom 1, uma unidade do mundo equivale a um pixel
+// e o eixo Y aponta para cima; `position` é o ponto do mundo no centro da tela.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera2D {
+    pub position: Vec2,
+    pub zoom: f32,      // Maior que 1 aproxima, menor que 1 afasta
+    pub rotation: f32,  // Rotação em radianos (anti-horário)
+    pub viewport: Vec2, // Tamanho da área de desenho em pixels
+}
+
+impl Camera2D {
+    pub fn new(viewport_width: u32, viewport_height: u32) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: Vec2::new(viewport_width as f32, viewport_height as f32),
+        }
+    }
+
+    pub fn set_viewport(&mut self, width: u32, height: u32) {
+        self.viewport = Vec2::new(width as f32, height as f32);
+    }
+
+    // Tamanho da região do mundo visível na tela
+    pub fn visible_size(&self) -> Vec2 {
+        self.viewport / self.zoom
+    }
+
+    pub fn view_projection(&self) -> Mat4 {
+        let half = self.visible_size() * 0.5;
+        let projection = Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, -1.0, 1.0);
+        let view = Mat4::from_rotation_z(-self.rotation) * Mat4::from_translation(-self.position.extend(0.0));
+        projection * view
+    }
+
+    // Converte uma posição em pixels (origem no canto superior esquerdo, Y para baixo)
+    // para coordenadas do mundo, por exemplo para saber o que está sob o mouse
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        let ndc = Vec2::new(
+            screen.x / self.viewport.x * 2.0 - 1.0,
+            1.0 - screen.y / self.viewport.y * 2.0,
+        );
+        let local = ndc * self.visible_size() * 0.5;
+        self.position + Vec2::from_angle(self.rotation).rotate(local)
+    }
+
+    // Converte coordenadas do mundo para pixels da tela
+    pub fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        let local = Vec2::from_angle(-self.rotation).rotate(world - self.position);
+        let ndc = local / (self.visible_size() * 0.5);
+        Vec2::new(
+            (ndc.x + 1.0) * 0.5 * self.viewport.x,
+            (1.0 - ndc.y) * 0.5 * self.viewport.y,
+        )
+    }
+}
+
+// Layout da câmera no uniform buffer (group 1 do shader de sprites)
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub(crate) struct CameraUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+impl From<&Camera2D> for CameraUniform {
+    fn from(camera: &Camera2D) -> Self {
+        Self {
+            view_proj: camera.view_projection().to_cols_array_2d(),
+        }
+    }
+}
+
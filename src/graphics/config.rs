@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+
+// Como os frames são apresentados na janela. Modos sem suporte no adaptador
+// caem para o próximo da lista até chegar em Vsync, que é sempre suportado.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum PresentMode {
+    #[default]
+    Vsync,      // Fifo: espera o monitor, sem tearing
+    Mailbox,    // Sem tearing e com menos latência (cai para Vsync)
+    Immediate,  // Sem espera, pode haver tearing (cai para Mailbox e depois Vsync)
+}
+
+impl PresentMode {
+    // Modos do wgpu aceitos, em ordem de preferência
+    fn candidates(self) -> &'static [wgpu::PresentMode] {
+        match self {
+            PresentMode::Vsync => &[wgpu::PresentMode::Fifo],
+            PresentMode::Mailbox => &[wgpu::PresentMode::Mailbox, wgpu::PresentMode::Fifo],
+            PresentMode::Immediate => &[
+                wgpu::PresentMode::Immediate,
+                wgpu::PresentMode::Mailbox,
+                wgpu::PresentMode::Fifo,
+            ],
+        }
+    }
+
+    // Escolhe o primeiro modo suportado pela surface
+    pub(crate) fn resolve(self, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+        self.candidates()
+            .iter()
+            .copied()
+            .find(|mode| supported.contains(mode))
+            .unwrap_or(wgpu::PresentMode::Fifo)
+    }
+}
+
+// Opções de criação do `Render`. O padrão reproduz o comportamento antigo:
+// backends primários, vsync, janela opaca, formato sRGB e sem MSAA.
+//
+//     let config = RenderConfig::new().present_mode(PresentMode::Mailbox).msaa(4);
+//     let render = Render::with_config(&window, &config).await?;
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderConfig {
+    pub present_mode: PresentMode,
+    pub backends: wgpu::Backends,
+    pub power_preference: wgpu::PowerPreference,
+    pub prefer_srgb: bool,              // Preferir um formato sRGB para a surface
+    pub transparent: bool,              // Janela transparente (a janela também precisa de `with_transparent(true)`)
+    pub sample_count: u32,              // Amostras de MSAA (1 = desligado); cai para o maior valor suportado
+    pub fallback_adapter: bool,         // Tentar o adaptador de software se não houver GPU
+    pub force_fallback_adapter: bool,   // Usar sempre o adaptador de software
+    pub shader_dir: Option<PathBuf>,    // Modo de desenvolvimento: lê os shaders dos sprites desta pasta
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::Vsync,
+            backends: wgpu::Backends::PRIMARY,
+            power_preference: wgpu::PowerPreference::default(),
+            prefer_srgb: true,
+            transparent: false,
+            sample_count: 1,
+            fallback_adapter: true,
+            force_fallback_adapter: false,
+            shader_dir: None,
+        }
+    }
+}
+
+impl RenderConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn present_mode(mut self, mode: PresentMode) -> Self {
+        self.present_mode = mode;
+        self
+    }
+
+    // Atalho: `true` para Vsync, `false` para Immediate
+    pub fn vsync(self, enabled: bool) -> Self {
+        self.present_mode(if enabled { PresentMode::Vsync } else { PresentMode::Immediate })
+    }
+
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    pub fn power_preference(mut self, preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = preference;
+        self
+    }
+
+    pub fn prefer_srgb(mut self, prefer: bool) -> Self {
+        self.prefer_srgb = prefer;
+        self
+    }
+
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn msaa(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count.max(1);
+        self
+    }
+
+    pub fn fallback_adapter(mut self, allow: bool) -> Self {
+        self.fallback_adapter = allow;
+        self
+    }
+
+    pub fn force_fallback_adapter(mut self, force: bool) -> Self {
+        self.force_fallback_adapter = force;
+        self
+    }
+
+    // Lê `sprite.vert.wgsl` e `sprite.frag.wgsl` desta pasta em vez dos embutidos no binário.
+    // Erros são mostrados com arquivo e linha e caem para os embutidos; com o hot reload do
+    // `AppRunner`, o pipeline é recriado quando os arquivos mudam.
+    pub fn shader_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.shader_dir = Some(dir.into());
+        self
+    }
+
+    // `shader_dir` apontando para os shaders no código-fonte da base (para mexer nos shaders padrão)
+    pub fn dev_shaders(self) -> Self {
+        self.shader_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src/graphics/shaders"))
+    }
+
+    // Primeiro formato da surface com o espaço de cor pedido; senão, o preferido do adaptador
+    pub(crate) fn choose_format(&self, formats: &[wgpu::TextureFormat]) -> Option<wgpu::TextureFormat> {
+        formats
+            .iter()
+            .copied()
+            .find(|format| format.describe().srgb == self.prefer_srgb)
+            .or_else(|| formats.first().copied())
+    }
+
+    pub(crate) fn choose_alpha_mode(&self, modes: &[wgpu::CompositeAlphaMode]) -> wgpu::CompositeAlphaMode {
+        let preferred: &[wgpu::CompositeAlphaMode] = if self.transparent {
+            &[
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+                wgpu::CompositeAlphaMode::Inherit,
+            ]
+        } else {
+            &[wgpu::CompositeAlphaMode::Opaque]
+        };
+        match preferred.iter().copied().find(|mode| modes.contains(mode)) {
+            Some(mode) => mode,
+            None => {
+                if self.transparent {
+                    eprintln!("Transparent windows are not supported by this surface, using {:?}", modes.first());
+                }
+                modes.first().copied().unwrap_or(wgpu::CompositeAlphaMode::Opaque)
+            }
+        }
+    }
+
+    // Maior número de amostras suportado pelo formato que não passe do pedido
+    pub(crate) fn choose_sample_count(&self, adapter: &wgpu::Adapter, format: wgpu::TextureFormat) -> u32 {
+        let features = adapter.get_texture_format_features(format);
+        let resolve = features.flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE);
+        let count = [8, 4, 2]
+            .into_iter()
+            .filter(|count| *count <= self.sample_count)
+            .find(|count| resolve && features.flags.sample_count_supported(*count))
+            .unwrap_or(1);
+        if count != self.sample_count {
+            eprintln!("MSAA x{} is not supported for {:?}, using x{}", self.sample_count, format, count);
+        }
+        count
+    }
+}
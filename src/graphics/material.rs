@@ -0,0 +1,409 @@
+// Materiais: fragment shaders próprios para sprites, com um uniform e texturas extras.
+//
+// O vertex shader é o dos sprites; o fragment shader do material recebe as mesmas entradas
+// e os mesmos grupos 0 (textura do sprite) e 1 (câmera), mais o grupo 2 do material:
+//
+//     @group(0) @binding(0) var sprite_texture: texture_2d<f32>;
+//     @group(0) @binding(1) var sprite_sampler: sampler;
+//     @group(2) @binding(0) var<uniform> material: MeuUniform;    // `Material::Uniform`
+//     @group(2) @binding(1) var material_sampler: sampler;
+//     @group(2) @binding(2) var extra_texture: texture_2d<f32>;   // `Material::textures()[0]`, [1] em 3...
+//
+//     struct FragmentInput {
+//         @location(0) tex_coords: vec2<f32>,
+//         @location(1) tint: vec4<f32>,
+//     };
+//
+//     @fragment
+//     fn main(input: FragmentInput) -> @location(0) vec4<f32> { ... }
+//
+// O `FragmentInput` precisa aparecer mesmo que o shader não use `tex_coords` nem `tint`: o
+// wgpu 0.15 confere a interface nos dois sentidos, e além de cada entrada do fragment shader
+// existir no vertex shader, cada saída do vertex shader precisa ser declarada como entrada
+// ("location[0] is provided by the previous stage output but is not consumed").
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use bytemuck::Pod;
+use wgpu::util::DeviceExt;
+
+use super::texture::{TextureHandle, TextureStore};
+
+// Tipo de material. O layout do grupo 2 é criado uma vez por tipo, no primeiro
+// `Render::add_material`, e os pipelines (um por modo de blend usado) ficam no cache do
+// `Render`; cada valor adicionado tem o seu uniform e bind group.
+pub trait Material: 'static {
+    type Uniform: Pod;
+
+    // Quantas texturas extras o shader declara (bindings 2, 3, ...)
+    const TEXTURE_COUNT: usize = 0;
+
+    // Código WGSL do fragment shader (ponto de entrada `main`), ex.: `include_str!("onda.wgsl")`
+    fn fragment_shader() -> &'static str;
+
+    fn uniform(&self) -> Self::Uniform;
+
+    // Texturas extras, na ordem dos bindings; precisa ter `TEXTURE_COUNT` elementos
+    fn textures(&self) -> Vec<TextureHandle> {
+        Vec::new()
+    }
+}
+
+// Identificador de um material adicionado ao `Render`, sem o tipo (é o que vai no `SpriteBatch`)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MaterialId {
+    index: u32,
+    generation: u32,
+}
+
+#[cfg(test)]
+impl MaterialId {
+    // Id sem material por trás, para testar a lógica de CPU sem GPU
+    pub(crate) fn for_test(index: u32) -> Self {
+        Self { index, generation: 0 }
+    }
+}
+
+// Identificador tipado, devolvido pelo `Render::add_material`
+pub struct MaterialHandle<M: Material> {
+    id: MaterialId,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M: Material> MaterialHandle<M> {
+    pub fn id(&self) -> MaterialId {
+        self.id
+    }
+}
+
+impl<M: Material> From<MaterialHandle<M>> for MaterialId {
+    fn from(handle: MaterialHandle<M>) -> Self {
+        handle.id
+    }
+}
+
+// Implementados à mão: o derive exigiria `M: Clone`, `M: PartialEq`...
+impl<M: Material> Clone for MaterialHandle<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: Material> Copy for MaterialHandle<M> {}
+
+impl<M: Material> PartialEq for MaterialHandle<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<M: Material> Eq for MaterialHandle<M> {}
+
+impl<M: Material> Hash for MaterialHandle<M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<M: Material> fmt::Debug for MaterialHandle<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MaterialHandle<{}>({:?})", std::any::type_name::<M>(), self.id)
+    }
+}
+
+// O que é comum a todos os materiais de um tipo (os pipelines ficam no `PipelineCache`)
+struct MaterialType {
+    layout: wgpu::BindGroupLayout,  // Grupo 2
+    pipeline_layout: wgpu::PipelineLayout,
+    fragment_source: &'static str,
+    name: &'static str,  // Nome do tipo, para as mensagens de erro
+}
+
+struct MaterialInstance {
+    type_id: TypeId,
+    textures: Vec<TextureHandle>,  // Para refazer o bind group quando uma delas é recriada
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+struct Slot {
+    generation: u32,
+    instance: Option<MaterialInstance>,
+}
+
+// Materiais do `Render`: layouts por tipo e bind groups por material
+#[derive(Default)]
+pub(crate) struct MaterialStore {
+    types: HashMap<TypeId, MaterialType>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl MaterialStore {
+    // Layout do grupo 2: uniform, sampler e as texturas extras
+    pub(crate) fn create_layout<M: Material>(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ];
+        entries.extend((0..M::TEXTURE_COUNT as u32).map(|i| wgpu::BindGroupLayoutEntry {
+            binding: 2 + i,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            },
+            count: None,
+        }));
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &entries,
+            label: Some(std::any::type_name::<M>()),
+        })
+    }
+
+    pub(crate) fn has_type<M: Material>(&self) -> bool {
+        self.types.contains_key(&TypeId::of::<M>())
+    }
+
+    pub(crate) fn insert_type<M: Material>(&mut self, layout: wgpu::BindGroupLayout, pipeline_layout: wgpu::PipelineLayout) {
+        let material_type = MaterialType {
+            layout,
+            pipeline_layout,
+            fragment_source: M::fragment_shader(),
+            name: std::any::type_name::<M>(),
+        };
+        self.types.insert(TypeId::of::<M>(), material_type);
+    }
+
+    // Um tipo cujo pipeline não pôde ser criado não fica registrado
+    pub(crate) fn remove_type<M: Material>(&mut self) {
+        self.types.remove(&TypeId::of::<M>());
+    }
+
+    // Layout, fragment shader e nome para criar um pipeline do tipo `type_id`
+    pub(crate) fn shader(&self, type_id: TypeId) -> Option<(&wgpu::PipelineLayout, &'static str, &'static str)> {
+        let material_type = self.types.get(&type_id)?;
+        Some((&material_type.pipeline_layout, material_type.fragment_source, material_type.name))
+    }
+
+    // O tipo de `M` precisa já existir (`insert_type`)
+    pub(crate) fn insert<M: Material>(&mut self, device: &wgpu::Device, textures: &TextureStore, material: &M) -> Result<MaterialHandle<M>> {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(std::any::type_name::<M>()),
+            contents: &uniform_bytes(material),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let handles = material.textures();
+        let bind_group = self.create_bind_group::<M>(device, textures, &handles, &uniform_buffer)?;
+        let instance = MaterialInstance {
+            type_id: TypeId::of::<M>(),
+            textures: handles,
+            uniform_buffer,
+            bind_group,
+        };
+
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.instance = Some(instance);
+                MaterialId { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, instance: Some(instance) });
+                MaterialId {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        };
+        Ok(MaterialHandle { id, marker: PhantomData })
+    }
+
+    // Envia o uniform novo e refaz o bind group (as texturas podem ter mudado)
+    pub(crate) fn update<M: Material>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        textures: &TextureStore,
+        handle: MaterialHandle<M>,
+        material: &M,
+    ) -> Result<()> {
+        let Some(instance) = self.instance(handle.id) else {
+            return Err(anyhow::anyhow!("Material inválido: {:?}", handle));
+        };
+        let handles = material.textures();
+        let bind_group = self.create_bind_group::<M>(device, textures, &handles, &instance.uniform_buffer)?;
+        queue.write_buffer(&instance.uniform_buffer, 0, &uniform_bytes(material));
+        if let Some(instance) = self.instance_mut(handle.id) {
+            instance.textures = handles;
+            instance.bind_group = bind_group;
+        }
+        Ok(())
+    }
+
+    // Refaz os bind groups dos materiais que usam `texture`, depois que a textura foi recriada
+    // (bind groups guardam a view antiga). Se outra textura do material já não existe, o bind
+    // group antigo é mantido.
+    pub(crate) fn refresh_texture(&mut self, device: &wgpu::Device, textures: &TextureStore, texture: TextureHandle) {
+        let rebuilt: Vec<(usize, wgpu::BindGroup)> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let instance = slot.instance.as_ref().filter(|instance| instance.textures.contains(&texture))?;
+                let bind_group = self
+                    .texture_bind_group(device, textures, instance.type_id, &instance.textures, &instance.uniform_buffer)
+                    .ok()?;
+                Some((index, bind_group))
+            })
+            .collect();
+        for (index, bind_group) in rebuilt {
+            if let Some(instance) = &mut self.slots[index].instance {
+                instance.bind_group = bind_group;
+            }
+        }
+    }
+
+    // Retorna `false` se o material já não existia
+    pub(crate) fn remove(&mut self, id: MaterialId) -> bool {
+        let Some(slot) = self.slots.get_mut(id.index as usize) else {
+            return false;
+        };
+        if slot.generation != id.generation || slot.instance.take().is_none() {
+            return false;
+        }
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        true
+    }
+
+    pub(crate) fn contains(&self, id: MaterialId) -> bool {
+        self.instance(id).is_some()
+    }
+
+    // Se algum material vivo amostra `texture` (ela não pode ser destruída enquanto isso)
+    pub(crate) fn uses_texture(&self, texture: TextureHandle) -> bool {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.instance.as_ref())
+            .any(|instance| instance.textures.contains(&texture))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.instance.is_some()).count()
+    }
+
+    // O que o `render_batch` precisa para desenhar com o material: o tipo (chave do pipeline) e o bind group
+    pub(crate) fn get(&self, id: MaterialId) -> Option<(TypeId, &wgpu::BindGroup)> {
+        let instance = self.instance(id)?;
+        Some((instance.type_id, &instance.bind_group))
+    }
+
+    fn instance(&self, id: MaterialId) -> Option<&MaterialInstance> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.instance.as_ref())
+    }
+
+    fn instance_mut(&mut self, id: MaterialId) -> Option<&mut MaterialInstance> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.instance.as_mut())
+    }
+
+    fn create_bind_group<M: Material>(
+        &self,
+        device: &wgpu::Device,
+        textures: &TextureStore,
+        handles: &[TextureHandle],
+        uniform_buffer: &wgpu::Buffer,
+    ) -> Result<wgpu::BindGroup> {
+        let name = std::any::type_name::<M>();
+        if !self.has_type::<M>() {
+            return Err(anyhow::anyhow!("Tipo do material {} não foi registrado", name));
+        }
+        if handles.len() != M::TEXTURE_COUNT {
+            return Err(anyhow::anyhow!(
+                "O material {} declara {} texturas, mas `textures()` devolveu {}",
+                name,
+                M::TEXTURE_COUNT,
+                handles.len()
+            ));
+        }
+        self.texture_bind_group(device, textures, TypeId::of::<M>(), handles, uniform_buffer)
+    }
+
+    fn texture_bind_group(
+        &self,
+        device: &wgpu::Device,
+        textures: &TextureStore,
+        type_id: TypeId,
+        handles: &[TextureHandle],
+        uniform_buffer: &wgpu::Buffer,
+    ) -> Result<wgpu::BindGroup> {
+        let material_type = self
+            .types
+            .get(&type_id)
+            .ok_or_else(|| anyhow::anyhow!("Tipo de material não registrado: {:?}", type_id))?;
+        let name = material_type.name;
+        let views = handles
+            .iter()
+            .map(|&handle| {
+                textures
+                    .get(handle)
+                    .map(|texture| texture.view())
+                    .ok_or_else(|| anyhow::anyhow!("Textura inválida no material {}: {:?}", name, handle))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(textures.sampler()),
+            },
+        ];
+        entries.extend(views.into_iter().enumerate().map(|(i, view)| wgpu::BindGroupEntry {
+            binding: 2 + i as u32,
+            resource: wgpu::BindingResource::TextureView(view),
+        }));
+        Ok(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &material_type.layout,
+            entries: &entries,
+            label: Some(name),
+        }))
+    }
+}
+
+// Bytes do uniform, completados até um múltiplo de 16 (uniform vazio ainda precisa de um buffer)
+fn uniform_bytes<M: Material>(material: &M) -> Vec<u8> {
+    let uniform = material.uniform();
+    let mut bytes = bytemuck::bytes_of(&uniform).to_vec();
+    bytes.resize(bytes.len().max(1).next_multiple_of(16), 0);
+    bytes
+}
@@ -1 +1,13 @@
-pub mod render;
\ No newline at end of file
+pub mod animation;
+pub mod atlas;
+pub mod bmfont;
+pub mod camera;
+pub mod config;
+pub mod material;
+pub mod render;
+pub mod shader;
+pub mod shapes;
+pub mod sprite;
+pub mod target;
+pub mod text;
+pub mod texture;
@@ -311,9 +311,21 @@ impl Render {
 use anyhow::Result;
 use wgpu::util::DeviceExt;
 use winit::window::Window;
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
 use bytemuck::{Pod, Zeroable};
 
+use super::camera::{Camera2D, CameraUniform};
+use super::config::{PresentMode, RenderConfig};
+use super::material::{Material, MaterialHandle, MaterialId, MaterialStore};
+use super::shader::{self, ShaderError};
+use super::shapes::ShapeRenderer;
+use super::sprite::{DrawCall, Sprite, SpriteBatch, SpriteInstance};
+use super::target::{RenderTarget, RenderTargetSize, RenderTargets, TargetInfo, RENDER_TARGET_FORMAT};
+use super::texture::{TextureHandle, TextureStore};
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]  // Agora a derivação está correta
 struct Vertex {
@@ -321,90 +333,406 @@ struct Vertex {
     tex_coords: [f32; 2],  // Coordenadas de textura (UV)
 }
 
+// Destino dos frames: a surface da janela ou uma textura offscreen (modo headless)
+enum FrameTarget {
+    Surface(wgpu::Surface),
+    Offscreen(wgpu::Texture),
+}
+
 pub struct Render {
     device: wgpu::Device,
     queue: wgpu::Queue,
-    surface: wgpu::Surface,
+    target: FrameTarget,
     config: wgpu::SurfaceConfiguration,
-    texture_bind_group_layout: wgpu::BindGroupLayout,
+    render_config: RenderConfig,  // Opções de criação (sample_count já com o valor efetivo)
+    present_modes: Vec<wgpu::PresentMode>,  // Modos de apresentação suportados pela surface
+    msaa_view: Option<wgpu::TextureView>,  // Textura multisample resolvida no destino (MSAA ligado)
+    clear_color: wgpu::Color,
+    textures: TextureStore,
     vertex_buffer: wgpu::Buffer,  // Adicionar o buffer de vértices
-    render_pipeline: wgpu::RenderPipeline,  // Adicionar o pipeline gráfico
+    instance_buffer: wgpu::Buffer,  // Dados por sprite do SpriteBatch
+    instance_capacity: usize,  // Quantos sprites cabem no instance buffer atual
+    camera: Camera2D,
+    camera_buffer: wgpu::Buffer,  // Uniform com a matriz view-projection
+    camera_bind_group: wgpu::BindGroup,
+    camera_bind_group_layout: wgpu::BindGroupLayout,  // Usado também pelos pipelines dos materiais
+    materials: MaterialStore,
+    render_targets: RenderTargets,
+    shapes: ShapeRenderer,  // Formas desenhadas por cima dos sprites no próximo frame
+    sprite_pipeline_layout: wgpu::PipelineLayout,  // Grupos 0 (textura) e 1 (câmera)
+    sprite_vertex_source: String,  // Shaders dos sprites em uso: os embutidos ou os da `shader_dir`
+    sprite_fragment_source: String,
+    pipelines: PipelineCache,
 }
 
-// Definir os vértices do sprite 2D (um quadrado)
+// Definir os vértices do sprite 2D (um quadrado), na ordem de um triangle strip
 const VERTICES: &[Vertex] = &[
     Vertex { position: [-0.5, -0.5], tex_coords: [0.0, 1.0] }, // Inferior esquerdo
     Vertex { position: [ 0.5, -0.5], tex_coords: [1.0, 1.0] }, // Inferior direito
-    Vertex { position: [ 0.5,  0.5], tex_coords: [1.0, 0.0] }, // Superior direito
     Vertex { position: [-0.5,  0.5], tex_coords: [0.0, 0.0] }, // Superior esquerdo
+    Vertex { position: [ 0.5,  0.5], tex_coords: [1.0, 0.0] }, // Superior direito
 ];
 
+// Erros ao desenhar um frame. Lost/Outdated já tiveram a surface reconfigurada e uma
+// nova tentativa feita; Timeout só pula o frame. Apenas OutOfMemory é fatal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RenderError {
+    SurfaceLost,
+    SurfaceOutdated,
+    Timeout,
+    OutOfMemory,
+}
+
+impl RenderError {
+    // Se verdadeiro, não adianta tentar o próximo frame
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, RenderError::OutOfMemory)
+    }
+}
+
+impl From<wgpu::SurfaceError> for RenderError {
+    fn from(error: wgpu::SurfaceError) -> Self {
+        match error {
+            wgpu::SurfaceError::Lost => RenderError::SurfaceLost,
+            wgpu::SurfaceError::Outdated => RenderError::SurfaceOutdated,
+            wgpu::SurfaceError::Timeout => RenderError::Timeout,
+            wgpu::SurfaceError::OutOfMemory => RenderError::OutOfMemory,
+        }
+    }
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::SurfaceLost => write!(f, "surface lost and could not be recreated"),
+            RenderError::SurfaceOutdated => write!(f, "surface outdated and could not be reconfigured"),
+            RenderError::Timeout => write!(f, "timed out acquiring the next frame, frame skipped"),
+            RenderError::OutOfMemory => write!(f, "out of memory while acquiring the next frame"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+// Como a cor de um sprite se combina com o que já está no destino
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum BlendMode {
+    #[default]
+    Alpha,          // Transparência comum (alfa não pré-multiplicado)
+    Additive,       // Soma a cor ponderada pelo alfa: brilho, fogo, partículas
+    Multiply,       // Multiplica o destino pela cor, ignorando o alfa: sombras, tingimento
+    Premultiplied,  // Cor já multiplicada pelo alfa (texturas exportadas assim)
+}
+
+impl BlendMode {
+    pub fn blend_state(self) -> wgpu::BlendState {
+        // O alfa do destino é mantido nos modos que não são de transparência
+        let keep_alpha = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Zero,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+        match self {
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+            BlendMode::Multiply => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+        }
+    }
+}
+
+// Shaders de um pipeline do cache
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PipelineShader {
+    Sprite,            // Shaders padrão dos sprites
+    Material(TypeId),  // Vertex shader dos sprites com o fragment shader de um `Material`
+}
+
+// Tudo o que distingue uma variante de pipeline
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    pub shader: PipelineShader,
+    pub blend: BlendMode,
+    pub topology: wgpu::PrimitiveTopology,
+    pub sample_count: u32,
+    pub format: wgpu::TextureFormat,
+}
+
+// Pipelines por variante, criados na primeira vez que são usados. Variantes que falharam
+// ficam registradas sem pipeline para o erro não se repetir a cada frame.
+#[derive(Default)]
+pub struct PipelineCache {
+    pipelines: HashMap<PipelineKey, Option<wgpu::RenderPipeline>>,
+}
+
+impl PipelineCache {
+    pub fn get(&self, key: &PipelineKey) -> Option<&wgpu::RenderPipeline> {
+        self.pipelines.get(key)?.as_ref()
+    }
+
+    // Se a variante já foi criada (ou já falhou)
+    pub fn contains(&self, key: &PipelineKey) -> bool {
+        self.pipelines.contains_key(key)
+    }
+
+    // Variantes criadas com sucesso
+    pub fn keys(&self) -> impl Iterator<Item = &PipelineKey> + '_ {
+        self.pipelines.iter().filter(|(_, pipeline)| pipeline.is_some()).map(|(key, _)| key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&mut self, key: PipelineKey, pipeline: Option<wgpu::RenderPipeline>) {
+        self.pipelines.insert(key, pipeline);
+    }
+}
+
+// Formato da textura offscreen do modo headless (mesma ordem de bytes do `image::RgbaImage`)
+const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+// Capacidade inicial do instance buffer (cresce conforme a demanda)
+const INITIAL_INSTANCE_CAPACITY: usize = 1024;
+
+// Shaders dos sprites embutidos no binário. Com `RenderConfig::shader_dir`, arquivos com
+// estes nomes são lidos daquela pasta (e podem ser recarregados com o jogo rodando).
+const SPRITE_VERTEX_SHADER: &str = "sprite.vert.wgsl";
+const SPRITE_FRAGMENT_SHADER: &str = "sprite.frag.wgsl";
+const SPRITE_VERTEX_SOURCE: &str = include_str!("shaders/sprite.vert.wgsl");
+const SPRITE_FRAGMENT_SOURCE: &str = include_str!("shaders/sprite.frag.wgsl");
+
 impl Render {
     pub async fn new(window: &Window) -> Result<Self> {
+        Self::with_config(window, &RenderConfig::default()).await
+    }
+
+    pub async fn with_config(window: &Window, render_config: &RenderConfig) -> Result<Self> {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::PRIMARY,
+            backends: render_config.backends,
             dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
         });
 
         let surface = unsafe { instance.create_surface(window) }
             .expect("Failed to create surface");
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
+        let adapter = Self::request_adapter(&instance, Some(&surface), render_config).await?;
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        // Escolher formato, modo de apresentação e transparência entre os suportados pela surface
+        let capabilities = surface.get_capabilities(&adapter);
+        let format = render_config
+            .choose_format(&capabilities.formats)
+            .ok_or_else(|| anyhow::anyhow!("No supported surface format found"))?;
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: window.inner_size().width,
+            height: window.inner_size().height,
+            present_mode: render_config.present_mode.resolve(&capabilities.present_modes),
+            alpha_mode: render_config.choose_alpha_mode(&capabilities.alpha_modes),
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        let mut render_config = render_config.clone();
+        render_config.sample_count = render_config.choose_sample_count(&adapter, format);
+
+        Ok(Self::build(
+            device,
+            queue,
+            FrameTarget::Surface(surface),
+            config,
+            render_config,
+            capabilities.present_modes,
+        ))
+    }
+
+    // Cria um renderizador sem janela, que desenha numa textura offscreen.
+    // O frame pode ser lido de volta com `read_pixels` (testes de imagem, CI).
+    // Sem GPU disponível, tenta o adaptador de fallback (renderização por software).
+    pub async fn new_headless(width: u32, height: u32) -> Result<Self> {
+        let render_config = RenderConfig::default().backends(wgpu::Backends::all());
+        Self::new_headless_with_config(width, height, &render_config).await
+    }
+
+    // Modo headless com opções; modo de apresentação e transparência não se aplicam
+    pub async fn new_headless_with_config(width: u32, height: u32, render_config: &RenderConfig) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(anyhow::anyhow!("Invalid headless size: {}x{}", width, height));
+        }
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: render_config.backends,
+            dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
+        });
+
+        let adapter = Self::request_adapter(&instance, None, render_config).await?;
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        // Não existe surface, mas a configuração guarda o tamanho e o formato do destino
+        let format = if render_config.prefer_srgb { HEADLESS_FORMAT } else { HEADLESS_FORMAT.remove_srgb_suffix() };
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+        };
+        let texture = Self::create_offscreen_texture(&device, &config);
+
+        let mut render_config = render_config.clone();
+        render_config.sample_count = render_config.choose_sample_count(&adapter, format);
+
+        Ok(Self::build(
+            device,
+            queue,
+            FrameTarget::Offscreen(texture),
+            config,
+            render_config,
+            vec![wgpu::PresentMode::Fifo],
+        ))
+    }
+
+    // Pede o adaptador conforme a configuração; sem adaptador de hardware,
+    // tenta o de software se `fallback_adapter` permitir
+    async fn request_adapter(
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface>,
+        render_config: &RenderConfig,
+    ) -> Result<wgpu::Adapter> {
+        let mut options = wgpu::RequestAdapterOptions {
+            power_preference: render_config.power_preference,
+            compatible_surface: surface,
+            force_fallback_adapter: render_config.force_fallback_adapter,
+        };
+        if let Some(adapter) = instance.request_adapter(&options).await {
+            return Ok(adapter);
+        }
+        if !render_config.fallback_adapter || options.force_fallback_adapter {
+            return Err(anyhow::anyhow!("Failed to find an appropriate adapter"));
+        }
+        options.force_fallback_adapter = true;
+        instance
+            .request_adapter(&options)
             .await
-            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
+            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter (fallback included)"))
+    }
 
+    async fn request_device(adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Queue)> {
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
+                    // Libera as contagens de MSAA específicas do adaptador (2x, 8x)
+                    features: adapter.features() & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                     limits: wgpu::Limits::default(),
                     label: None,
                 },
                 None,
             )
             .await?;
+        Ok((device, queue))
+    }
 
-        // Verificar o formato disponível da surface
-        let formats = surface.get_capabilities(&adapter).formats;
-        let format = formats.get(0).ok_or_else(|| anyhow::anyhow!("No supported surface format found"))?;
+    fn create_offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen_texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        })
+    }
 
-        let config = wgpu::SurfaceConfiguration {
+    // Textura multisample do tamanho do destino; `None` com MSAA desligado
+    fn create_msaa_view(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Option<wgpu::TextureView> {
+        if sample_count <= 1 {
+            return None;
+        }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("msaa_texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: *format,
-            width: window.inner_size().width,
-            height: window.inner_size().height,
-            present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
-            view_formats: vec![],
-        };
-        surface.configure(&device, &config);
+            view_formats: &[],
+        });
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
 
-        // Criar layout de binding de textura
-        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
+    // Cria os recursos que não dependem do destino (layouts, buffers e pipeline)
+    fn build(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        target: FrameTarget,
+        config: wgpu::SurfaceConfiguration,
+        render_config: RenderConfig,
+        present_modes: Vec<wgpu::PresentMode>,
+    ) -> Self {
+        // Registro de texturas (cria o layout de binding de textura e o sampler compartilhado)
+        let textures = TextureStore::new(&device);
+
+        // Criar o uniform da câmera (group 1 do shader de vértice)
+        let camera = Camera2D::new(config.width, config.height);
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::cast_slice(&[CameraUniform::from(&camera)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
                 },
-            ],
-            label: Some("texture_bind_group_layout"),
+                count: None,
+            }],
+            label: Some("camera_bind_group_layout"),
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+            label: Some("camera_bind_group"),
         });
 
         // Criar o vertex buffer
@@ -413,28 +741,151 @@ impl Render {
             contents: bytemuck::cast_slice(VERTICES),
             usage: wgpu::BufferUsages::VERTEX,
         });
+        let instance_buffer = Self::create_instance_buffer(&device, INITIAL_INSTANCE_CAPACITY);
 
+        // Layout dos pipelines dos sprites; os pipelines são criados no cache quando usados
+        // (com os shaders embutidos; os da `shader_dir` são carregados no fim)
+        let sprite_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[textures.layout(), &camera_bind_group_layout],  // Layouts da textura e da câmera
+            push_constant_ranges: &[],
+        });
+
+        let shapes = ShapeRenderer::new(&device, &camera_bind_group_layout, config.format, render_config.sample_count);
+        let msaa_view = Self::create_msaa_view(&device, &config, render_config.sample_count);
+
+        // Janela transparente precisa limpar com alfa zero para o fundo aparecer
+        let clear_color = if render_config.transparent && config.alpha_mode != wgpu::CompositeAlphaMode::Opaque {
+            wgpu::Color::TRANSPARENT
+        } else {
+            wgpu::Color::BLACK
+        };
+
+        let mut render = Self {
+            device,
+            queue,
+            target,
+            config,
+            render_config,
+            present_modes,
+            msaa_view,
+            clear_color,
+            textures,
+            vertex_buffer,
+            instance_buffer,
+            instance_capacity: INITIAL_INSTANCE_CAPACITY,
+            camera,
+            camera_buffer,
+            camera_bind_group,
+            camera_bind_group_layout,
+            materials: MaterialStore::default(),
+            render_targets: RenderTargets::default(),
+            shapes,
+            sprite_pipeline_layout,
+            sprite_vertex_source: String::from(SPRITE_VERTEX_SOURCE),
+            sprite_fragment_source: String::from(SPRITE_FRAGMENT_SOURCE),
+            pipelines: PipelineCache::default(),
+        };
+        if render.render_config.shader_dir.is_some() {
+            if let Err(e) = render.reload_shaders() {
+                eprintln!("{}", e);
+            }
+        }
+        render
+    }
+
+    // Chave do pipeline de sprites para desenhar no frame
+    fn pipeline_key(&self, shader: PipelineShader, blend: BlendMode) -> PipelineKey {
+        Self::pipeline_key_for(shader, blend, self.config.format, self.render_config.sample_count)
+    }
+
+    // Chave do pipeline de sprites para um destino com outro formato (render targets)
+    fn pipeline_key_for(shader: PipelineShader, blend: BlendMode, format: wgpu::TextureFormat, sample_count: u32) -> PipelineKey {
+        PipelineKey {
+            shader,
+            blend,
+            topology: wgpu::PrimitiveTopology::TriangleStrip,  // Para desenhar o quadrado
+            sample_count,
+            format,
+        }
+    }
+
+    // Cria a variante no cache se ela ainda não existir (erros são mostrados uma vez)
+    fn prepare_pipeline(&mut self, key: PipelineKey) {
+        if self.pipelines.contains(&key) {
+            return;
+        }
+        let pipeline = match self.create_pipeline(&key, &self.sprite_vertex_source, &self.sprite_fragment_source) {
+            Ok(pipeline) => Some(pipeline),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        };
+        self.pipelines.insert(key, pipeline);
+    }
+
+    // O naga não confere a interface com o pipeline (bindings, atributos); o escopo de erro
+    // pega o que sobrar em vez do pânico padrão do wgpu
+    fn create_pipeline(&self, key: &PipelineKey, vertex_source: &str, sprite_fragment_source: &str) -> Result<wgpu::RenderPipeline, ShaderError> {
+        let vertex_name = self.sprite_shader_name(SPRITE_VERTEX_SHADER);
+        let (layout, fragment_source, fragment_name) = match key.shader {
+            PipelineShader::Sprite => (&self.sprite_pipeline_layout, sprite_fragment_source, self.sprite_shader_name(SPRITE_FRAGMENT_SHADER)),
+            PipelineShader::Material(type_id) => {
+                let (layout, fragment_source, name) = self
+                    .materials
+                    .shader(type_id)
+                    .ok_or_else(|| ShaderError::new("material", "Tipo de material não registrado"))?;
+                (layout, fragment_source, String::from(name))
+            }
+        };
+
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipeline = Self::create_sprite_pipeline(&self.device, layout, vertex_source, fragment_source, key);
+        let Some(error) = pollster::block_on(self.device.pop_error_scope()) else {
+            return Ok(pipeline);
+        };
+        // O wgpu diz qual estágio não bate com o pipeline ("error matching FRAGMENT shader requirements...");
+        // sem isso, o erro pode ser de qualquer um dos dois arquivos
+        let message = error.to_string();
+        let name = if message.contains("matching VERTEX shader") {
+            vertex_name
+        } else if message.contains("matching FRAGMENT shader") {
+            fragment_name
+        } else {
+            format!("{} + {}", vertex_name, fragment_name)
+        };
+        Err(ShaderError::new(name, message))
+    }
+
+    // Caminho do shader de sprites nas mensagens de erro (só o nome quando é o embutido)
+    fn sprite_shader_name(&self, file: &str) -> String {
+        match &self.render_config.shader_dir {
+            Some(dir) => dir.join(file).display().to_string(),
+            None => String::from(file),
+        }
+    }
+
+    fn create_sprite_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        vertex_source: &str,
+        fragment_source: &str,
+        key: &PipelineKey,
+    ) -> wgpu::RenderPipeline {
         // Carregar os shaders(Wgsl)
         let vertex_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Vertex Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/sprite.vert.wgsl").into()),  // WGSL shader como alternativa
+            source: wgpu::ShaderSource::Wgsl(vertex_source.into()),
         });
         let fragment_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Fragment Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/sprite.frag.wgsl").into()),  // WGSL shader como alternativa
-            });
-      
-
-        // Pipeline gráfico
-        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&texture_bind_group_layout],  // Layout da textura
-            push_constant_ranges: &[],
+            source: wgpu::ShaderSource::Wgsl(fragment_source.into()),
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &vertex_shader_module,
                 entry_point: "main",  // Ponto de entrada do shader de vértice
@@ -455,37 +906,84 @@ impl Render {
                             },
                         ],
                     },
+                    SpriteInstance::layout(),  // Dados por instância (sprite)
                 ],
             },
             fragment: Some(wgpu::FragmentState {
                 module: &fragment_shader_module,
                 entry_point: "main",  // Ponto de entrada do shader de fragmento
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    format: key.format,
+                    blend: Some(key.blend.blend_state()),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
             primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,  // Para desenhar o quadrado
+                topology: key.topology,
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
+                cull_mode: None,  // Escala negativa inverte a ordem dos vértices (sprite espelhado)
                 ..Default::default()
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: key.sample_count,
+                ..Default::default()
+            },
             multiview: None,
-        });
+        })
+    }
 
-        Ok(Self {
-            device,
-            queue,
-            surface,
-            config,
-            texture_bind_group_layout,
-            vertex_buffer,
-            render_pipeline,
+    // Relê os shaders dos sprites da `RenderConfig::shader_dir` e recria com eles todas as
+    // variantes do cache (inclusive as dos materiais, que usam o mesmo vertex shader).
+    // Se algum não compilar ou alguma variante falhar, os pipelines anteriores continuam em uso
+    // e o erro aponta arquivo e linha.
+    pub fn reload_shaders(&mut self) -> Result<(), ShaderError> {
+        let Some(dir) = self.render_config.shader_dir.as_deref() else {
+            return Ok(());
+        };
+        let vertex_source = shader::load_wgsl(&dir.join(SPRITE_VERTEX_SHADER), wgpu::ShaderStages::VERTEX, "main")?;
+        let fragment_source = shader::load_wgsl(&dir.join(SPRITE_FRAGMENT_SHADER), wgpu::ShaderStages::FRAGMENT, "main")?;
+
+        // A variante padrão sempre é testada, mesmo com o cache vazio. As que tinham falhado
+        // saem do cache e são tentadas de novo quando forem usadas.
+        let mut keys: Vec<PipelineKey> = self.pipelines.keys().copied().collect();
+        let default_key = self.pipeline_key(PipelineShader::Sprite, BlendMode::Alpha);
+        if !keys.contains(&default_key) {
+            keys.push(default_key);
+        }
+        let mut rebuilt = PipelineCache::default();
+        for key in keys {
+            let pipeline = self.create_pipeline(&key, &vertex_source, &fragment_source)?;
+            rebuilt.insert(key, Some(pipeline));
+        }
+        self.sprite_vertex_source = vertex_source;
+        self.sprite_fragment_source = fragment_source;
+        self.pipelines = rebuilt;
+        Ok(())
+    }
+
+    // Recarrega os shaders se algum dos arquivos alterados (`AssetServer::changed_files`) for
+    // da `shader_dir`. Retorna se recarregou.
+    pub fn reload_changed_shaders(&mut self, changed: &[PathBuf]) -> Result<bool, ShaderError> {
+        // Chamado todo frame: sem arquivos alterados não há nem o que canonicalizar
+        let Some(dir) = self.render_config.shader_dir.as_deref().filter(|_| !changed.is_empty()) else {
+            return Ok(false);
+        };
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let names = [SPRITE_VERTEX_SHADER, SPRITE_FRAGMENT_SHADER].map(|name| dir.join(name));
+        if !changed.iter().any(|path| names.contains(path)) {
+            return Ok(false);
+        }
+        self.reload_shaders().map(|()| true)
+    }
+
+    fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance Buffer"),
+            size: (capacity * std::mem::size_of::<SpriteInstance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         })
     }
 
@@ -494,23 +992,109 @@ impl Render {
         if new_size.width > 0 && new_size.height > 0 {
             self.config.width = new_size.width;
             self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
+            self.camera.set_viewport(new_size.width, new_size.height);
+            match &mut self.target {
+                FrameTarget::Surface(surface) => surface.configure(&self.device, &self.config),
+                FrameTarget::Offscreen(texture) => {
+                    *texture = Self::create_offscreen_texture(&self.device, &self.config);
+                }
+            }
+            self.msaa_view = Self::create_msaa_view(&self.device, &self.config, self.render_config.sample_count);
+
+            // Render targets ligados à janela acompanham o novo tamanho (e os materiais que os usam
+            // passam a apontar para a textura nova)
+            for (target, info) in self.render_targets.window_sized() {
+                let (width, height) = info.size.resolve(self.size());
+                self.textures.resize_target(&self.device, target.texture(), width, height, RENDER_TARGET_FORMAT, info.filter);
+                self.materials.refresh_texture(&self.device, &self.textures, target.texture());
+            }
         }
     }
 
-    // Método de renderização
-    pub fn render(&mut self, bind_group: &wgpu::BindGroup) -> Result<()> {
-        let frame = match self.surface.get_current_texture() {
-            Ok(frame) => frame,
+    // Troca o modo de apresentação em tempo de execução (reconfigura a surface)
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.render_config.present_mode = mode;
+        self.config.present_mode = mode.resolve(&self.present_modes);
+        if let FrameTarget::Surface(surface) = &self.target {
+            surface.configure(&self.device, &self.config);
+        }
+    }
+
+    pub fn set_vsync(&mut self, enabled: bool) {
+        self.set_present_mode(if enabled { PresentMode::Vsync } else { PresentMode::Immediate });
+    }
+
+    // Se o modo efetivamente em uso sincroniza com o monitor (sem tearing). Mailbox também conta:
+    // não bloqueia, mas só troca o frame exibido no vblank.
+    pub fn vsync(&self) -> bool {
+        matches!(
+            self.config.present_mode,
+            wgpu::PresentMode::Fifo | wgpu::PresentMode::FifoRelaxed | wgpu::PresentMode::Mailbox
+        )
+    }
+
+    pub fn render_config(&self) -> &RenderConfig {
+        &self.render_config
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.render_config.sample_count
+    }
+
+    // Tamanho atual do destino (janela ou textura offscreen), em pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    pub fn surface_format(&self) -> wgpu::TextureFormat {
+        self.config.format
+    }
+
+    pub fn camera(&self) -> &Camera2D {
+        &self.camera
+    }
+
+    // A câmera é enviada para a GPU no início de cada `render_batch`
+    pub fn camera_mut(&mut self) -> &mut Camera2D {
+        &mut self.camera
+    }
+
+    pub fn shapes(&self) -> &ShapeRenderer {
+        &self.shapes
+    }
+
+    // As formas adicionadas aqui são desenhadas (e descartadas) no próximo `render_batch`
+    pub fn shapes_mut(&mut self) -> &mut ShapeRenderer {
+        &mut self.shapes
+    }
+
+    // Desenha a textura inteira num único quad centrado na câmera, ocupando metade da área visível
+    pub fn render(&mut self, texture: TextureHandle) -> Result<(), RenderError> {
+        let sprite = Sprite {
+            position: self.camera.position.into(),
+            scale: (self.camera.visible_size() * 0.5).into(),
+            ..Default::default()
+        };
+        let mut batch = SpriteBatch::new();
+        batch.push(texture, sprite);
+        self.render_batch(&batch)
+    }
+
+    // Método de renderização: envia os sprites do batch e desenha com chamadas instanciadas
+    pub fn render_batch(&mut self, batch: &SpriteBatch) -> Result<(), RenderError> {
+        // No modo headless não há frame para apresentar, desenhamos direto na textura offscreen
+        let (frame, view) = match self.acquire_frame() {
+            Ok(acquired) => acquired,
             Err(e) => {
-                eprintln!("Failed to acquire next swap chain texture: {}", e);
-                return Err(anyhow::anyhow!("Render error: {:?}", e));
+                self.shapes.clear();  // O frame foi pulado, as formas dele também
+                return Err(e);
             }
         };
 
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let format = self.config.format;
+        let sample_count = self.render_config.sample_count;
+        let draws = self.prepare_batch(batch, CameraUniform::from(&self.camera), format, sample_count);
+        self.shapes.prepare(&self.device, &self.queue);
 
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
@@ -518,11 +1102,12 @@ impl Render {
 
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                // Com MSAA desenhamos na textura multisample e resolvemos no frame
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view: self.msaa_view.as_ref().unwrap_or(&view),
+                    resolve_target: self.msaa_view.as_ref().map(|_| &view),
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), // Cor de fundo preta
+                        load: wgpu::LoadOp::Clear(self.clear_color), // Preto, ou transparente em janelas transparentes
                         store: true,
                     },
                 })],
@@ -530,93 +1115,487 @@ impl Render {
                 label: Some("Render Pass"),
             });
 
-            render_pass.set_pipeline(&self.render_pipeline);  // Define o pipeline
-            render_pass.set_bind_group(0, bind_group, &[]);   // Define o bind group da textura
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));  // Define o buffer de vértices
-            render_pass.draw(0..4, 0..1);  // Desenha 4 vértices (um quadrado)
+            self.draw_sprites(&mut render_pass, &draws, format, sample_count, None);
+            self.shapes.draw(&mut render_pass, &self.camera_bind_group);  // Formas por cima dos sprites
         }
 
         self.queue.submit(Some(encoder.finish()));
-        frame.present();
+        if let Some(frame) = frame {
+            frame.present();
+        }
+        self.shapes.clear();
 
         Ok(())
     }
 
-    // Função para carregar uma textura de imagem e criar um bind group
-pub fn load_texture(&self, image_path: &str) -> Result<(wgpu::Texture, wgpu::BindGroup)> {
-    let img = image::open(image_path).map_err(|e| {
-        eprintln!("Erro ao abrir a imagem: {}", e);
-        anyhow::anyhow!("Erro ao carregar a imagem")
-    })?.to_rgba8(); // Carrega a imagem
-    let dimensions = img.dimensions();
-
-    let texture_size = wgpu::Extent3d {
-        width: dimensions.0,
-        height: dimensions.1,
-        depth_or_array_layers: 1,
-    };
-
-    // Cria a textura na GPU antes de enviar os dados
-    let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("sprite_texture"),
-        size: texture_size,
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rgba8UnormSrgb,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[],
-    });
-
-    // Arredondar bytes_per_row para múltiplo de 256
-    let row_bytes = 4 * dimensions.0;  // 4 bytes por pixel (RGBA)
-    let padded_row_bytes = (row_bytes + 255) & !255;  // Arredondar para múltiplo de 256
-
-    // Envia os dados da imagem para a GPU
-    self.queue.write_texture(
-        wgpu::ImageCopyTexture {
-            texture: &texture,
-            mip_level: 0,
-            origin: wgpu::Origin3d::ZERO,
-            aspect: wgpu::TextureAspect::All,
-        },
-        &img,
-        wgpu::ImageDataLayout {
-            offset: 0,
-            bytes_per_row: Some(NonZeroU32::new(padded_row_bytes).unwrap()),  // Usar Some(NonZeroU32)
-            rows_per_image: Some(NonZeroU32::new(dimensions.1).unwrap()),     // Usar Some(NonZeroU32)
-        },
-        texture_size,
-    );
-
-    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-    let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
-        address_mode_u: wgpu::AddressMode::ClampToEdge,
-        address_mode_v: wgpu::AddressMode::ClampToEdge,
-        address_mode_w: wgpu::AddressMode::ClampToEdge,
-        mag_filter: wgpu::FilterMode::Linear,
-        min_filter: wgpu::FilterMode::Linear,
-        mipmap_filter: wgpu::FilterMode::Nearest,
-        ..Default::default()
-    });
-
-    let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-        layout: &self.texture_bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::TextureView(&texture_view),
+    // Desenha o batch num render target, visto por `camera`. O target é limpo (transparente) antes;
+    // as formas do `shapes_mut` continuam reservadas para o frame. Sprites com a própria
+    // textura do target são ignorados (não dá para ler e escrever a mesma textura no passe).
+    pub fn render_to_target(&mut self, target: RenderTarget, batch: &SpriteBatch, camera: &Camera2D) -> Result<()> {
+        if self.render_targets.get(target).is_none() || !self.textures.contains(target.texture()) {
+            return Err(anyhow::anyhow!("Invalid render target: {:?}", target));
+        }
+        let draws = self.prepare_batch(batch, CameraUniform::from(camera), RENDER_TARGET_FORMAT, 1);
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Target Encoder"),
+        });
+        {
+            let Some(texture) = self.textures.get(target.texture()) else {
+                return Err(anyhow::anyhow!("Invalid render target: {:?}", target));
+            };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: texture.view(),
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                label: Some("Render Target Pass"),
+            });
+            self.draw_sprites(&mut render_pass, &draws, RENDER_TARGET_FORMAT, 1, Some(target.texture()));
+        }
+        // Enviado já: o instance buffer e a câmera são reescritos pelo próximo passe
+        self.queue.submit(Some(encoder.finish()));
+        Ok(())
+    }
+
+    // Cria os pipelines que o batch vai usar e envia os sprites e a câmera para a GPU
+    fn prepare_batch(&mut self, batch: &SpriteBatch, camera: CameraUniform, format: wgpu::TextureFormat, sample_count: u32) -> Vec<DrawCall> {
+        let (instances, draws) = batch.prepare();
+        for draw in &draws {
+            let shader = match draw.material {
+                None => Some(PipelineShader::Sprite),
+                Some(material) => self.materials.get(material).map(|(type_id, _)| PipelineShader::Material(type_id)),
+            };
+            if let Some(shader) = shader {
+                self.prepare_pipeline(Self::pipeline_key_for(shader, draw.blend, format, sample_count));
+            }
+        }
+
+        // Aumentar o instance buffer se o batch não couber
+        if instances.len() > self.instance_capacity {
+            self.instance_capacity = instances.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(&self.device, self.instance_capacity);
+        }
+        if !instances.is_empty() {
+            self.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        }
+        self.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera]));
+        draws
+    }
+
+    // Grava as chamadas de draw do batch no passe; `skip` é a textura de destino, se houver
+    fn draw_sprites<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        draws: &[DrawCall],
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        skip: Option<TextureHandle>,
+    ) {
+        render_pass.set_bind_group(1, &self.camera_bind_group, &[]);  // Define a câmera
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));  // Define o buffer de vértices
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));  // Define o buffer de instâncias
+        let mut current_pipeline = None;  // (material, blend) do pipeline atual
+        for draw in draws {
+            if Some(draw.texture) == skip {
+                continue;
+            }
+            // Texturas e materiais removidos depois de entrarem no batch são ignorados
+            let Some(bind_group) = self.textures.bind_group(draw.texture) else {
+                continue;
+            };
+            if current_pipeline != Some((draw.material, draw.blend)) {
+                let shader = match draw.material {
+                    None => PipelineShader::Sprite,
+                    Some(material) => {
+                        let Some((type_id, material_bind_group)) = self.materials.get(material) else {
+                            continue;
+                        };
+                        render_pass.set_bind_group(2, material_bind_group, &[]);
+                        PipelineShader::Material(type_id)
+                    }
+                };
+                // Variantes que não compilaram são puladas
+                let Some(pipeline) = self.pipelines.get(&Self::pipeline_key_for(shader, draw.blend, format, sample_count)) else {
+                    continue;
+                };
+                render_pass.set_pipeline(pipeline);  // Define o pipeline
+                current_pipeline = Some((draw.material, draw.blend));
+            }
+            render_pass.set_bind_group(0, bind_group, &[]);   // Define o bind group da textura
+            render_pass.draw(0..4, draw.instances.clone());  // Desenha 4 vértices (um quadrado) por sprite
+        }
+    }
+
+    // Pega a próxima textura da surface. Em Lost/Outdated (comum ao minimizar no Linux/Wayland)
+    // a surface é reconfigurada e a aquisição é tentada mais uma vez.
+    fn acquire_frame(&self) -> Result<(Option<wgpu::SurfaceTexture>, wgpu::TextureView), RenderError> {
+        let surface = match &self.target {
+            FrameTarget::Surface(surface) => surface,
+            FrameTarget::Offscreen(texture) => {
+                return Ok((None, texture.create_view(&wgpu::TextureViewDescriptor::default())));
+            }
+        };
+
+        let frame = match surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                surface.configure(&self.device, &self.config);
+                surface.get_current_texture()?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        Ok((Some(frame), view))
+    }
+
+    // Copia o último frame desenhado de volta para a CPU (apenas no modo headless)
+    pub fn read_pixels(&self) -> Result<image::RgbaImage> {
+        let texture = match &self.target {
+            FrameTarget::Offscreen(texture) => texture,
+            FrameTarget::Surface(_) => {
+                return Err(anyhow::anyhow!("read_pixels is only available in headless mode"));
+            }
+        };
+
+        self.read_texture(texture, self.config.width, self.config.height)
+    }
+
+    // Copia o conteúdo atual de um render target para a CPU
+    pub fn read_target_pixels(&self, target: RenderTarget) -> Result<image::RgbaImage> {
+        let texture = self
+            .render_targets
+            .get(target)
+            .and_then(|_| self.textures.get(target.texture()))
+            .ok_or_else(|| anyhow::anyhow!("Invalid render target: {:?}", target))?;
+        self.read_texture(texture.texture(), texture.width(), texture.height())
+    }
+
+    // Textura RGBA8 (4 bytes por pixel) -> imagem
+    fn read_texture(&self, texture: &wgpu::Texture, width: u32, height: u32) -> Result<image::RgbaImage> {
+        // Cada linha copiada para o buffer precisa estar alinhada a COPY_BYTES_PER_ROW_ALIGNMENT (256)
+        let row_bytes = 4 * width;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_row_bytes * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_row_bytes),
+                    rows_per_image: NonZeroU32::new(height),
+                },
             },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: wgpu::BindingResource::Sampler(&sampler),
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
             },
-        ],
-        label: Some("texture_bind_group"),
-    });
+        );
+        self.queue.submit(Some(encoder.finish()));
 
-    Ok((texture, bind_group))
-}
+        // Mapear o buffer e esperar a GPU terminar a cópia
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv()??;
+
+        // Remover o preenchimento de cada linha
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+        for row in data.chunks(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+        drop(data);
+        buffer.unmap();
+
+        image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow::anyhow!("Readback buffer has an unexpected size"))
+    }
+
+    // Carrega uma imagem do disco como textura (o mesmo arquivo devolve sempre o mesmo handle)
+    pub fn load_texture(&mut self, image_path: impl AsRef<Path>) -> Result<TextureHandle> {
+        self.textures.load(&self.device, &self.queue, image_path)
+    }
+
+    // Cria uma textura a partir de uma imagem já em memória
+    pub fn create_texture(&mut self, img: &image::RgbaImage, label: &str) -> TextureHandle {
+        self.textures.insert_image(&self.device, &self.queue, img, label)
+    }
+
+    // Substitui uma região da textura a partir de (x, y)
+    pub fn update_texture(&self, texture: TextureHandle, x: u32, y: u32, img: &image::RgbaImage) -> bool {
+        self.textures.write_region(&self.queue, texture, x, y, img)
+    }
+
+    // Troca o conteúdo da textura sem invalidar o handle (sprites e atlas continuam apontando para ela).
+    // Retorna `false` para a textura de um render target: a recriada não poderia mais ser desenhada
+    // nem acompanharia a janela; use `resize_render_target` e desenhe nele.
+    pub fn replace_texture(&mut self, texture: TextureHandle, img: &image::RgbaImage) -> bool {
+        if self.render_targets.contains_texture(texture) || !self.textures.replace(&self.device, &self.queue, texture, img) {
+            return false;
+        }
+        self.materials.refresh_texture(&self.device, &self.textures, texture);
+        true
+    }
+
+    // Relê do disco a textura carregada de `image_path` por `load_texture`, se houver
+    pub fn reload_texture(&mut self, image_path: impl AsRef<Path>) -> Result<Option<TextureHandle>> {
+        let texture = self.textures.reload(&self.device, &self.queue, image_path)?;
+        if let Some(texture) = texture {
+            self.materials.refresh_texture(&self.device, &self.textures, texture);
+        }
+        Ok(texture)
+    }
+
+    // Libera a textura da GPU; o handle deixa de ser válido.
+    // Retorna `false` (e não libera) se algum material ainda usa a textura: remova ou atualize
+    // o material antes.
+    pub fn unload_texture(&mut self, texture: TextureHandle) -> bool {
+        if self.materials.uses_texture(texture) {
+            return false;
+        }
+        self.textures.unload(texture)
+    }
+
+    pub fn texture_size(&self, texture: TextureHandle) -> Option<(u32, u32)> {
+        self.textures.dimensions(texture)
+    }
+
+    pub fn textures(&self) -> &TextureStore {
+        &self.textures
+    }
+
+    pub fn pipeline_cache(&self) -> &PipelineCache {
+        &self.pipelines
+    }
+
+    // Cria um render target. `filter` é a amostragem quando ele é desenhado como sprite:
+    // `Nearest` amplia pixel art sem borrar.
+    pub fn create_render_target(&mut self, size: RenderTargetSize, filter: wgpu::FilterMode) -> Result<RenderTarget> {
+        let (width, height) = self.render_target_dimensions(size)?;
+        let texture = self.textures.insert_target(&self.device, width, height, RENDER_TARGET_FORMAT, filter);
+        Ok(self.render_targets.insert(texture, TargetInfo { size, filter }))
+    }
 
+    // Muda o tamanho (ou a ligação com a janela) de um render target; o conteúdo se perde
+    pub fn resize_render_target(&mut self, target: RenderTarget, size: RenderTargetSize) -> Result<()> {
+        let (width, height) = self.render_target_dimensions(size)?;
+        let info = self
+            .render_targets
+            .get_mut(target)
+            .ok_or_else(|| anyhow::anyhow!("Invalid render target: {:?}", target))?;
+        if !self.textures.resize_target(&self.device, target.texture(), width, height, RENDER_TARGET_FORMAT, info.filter) {
+            return Err(anyhow::anyhow!("Invalid render target: {:?}", target));
+        }
+        info.size = size;
+        self.materials.refresh_texture(&self.device, &self.textures, target.texture());
+        Ok(())
+    }
+
+    // Retorna `false` se o render target já tinha sido destruído ou se algum material ainda o
+    // usa como textura (nesse caso ele continua valendo)
+    pub fn destroy_render_target(&mut self, target: RenderTarget) -> bool {
+        if self.materials.uses_texture(target.texture()) {
+            return false;
+        }
+        self.render_targets.remove(target) && self.textures.unload(target.texture())
+    }
+
+    // Tamanho atual em pixels
+    pub fn render_target_size(&self, target: RenderTarget) -> Option<(u32, u32)> {
+        self.render_targets.get(target)?;
+        self.textures.dimensions(target.texture())
+    }
+
+    fn render_target_dimensions(&self, size: RenderTargetSize) -> Result<(u32, u32)> {
+        let (width, height) = size.resolve(self.size());
+        let max = self.device.limits().max_texture_dimension_2d;
+        if width == 0 || height == 0 || width > max || height > max {
+            return Err(anyhow::anyhow!("Invalid render target size: {}x{}", width, height));
+        }
+        Ok((width, height))
+    }
+
+    // Adiciona um material; o pipeline do tipo `M` é criado (e o shader validado) na primeira vez.
+    // Desenhe com `SpriteBatch::push_with_material`.
+    pub fn add_material<M: Material>(&mut self, material: &M) -> Result<MaterialHandle<M>> {
+        if !self.materials.has_type::<M>() {
+            let name = std::any::type_name::<M>();
+            shader::validate_wgsl(M::fragment_shader(), name, wgpu::ShaderStages::FRAGMENT, "main")?;
+
+            let layout = MaterialStore::create_layout::<M>(&self.device);
+            let pipeline_layout = self.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(name),
+                bind_group_layouts: &[self.textures.layout(), &self.camera_bind_group_layout, &layout],
+                push_constant_ranges: &[],
+            });
+            self.materials.insert_type::<M>(layout, pipeline_layout);
+
+            // Bindings que não batem com o layout só aparecem ao criar o pipeline, não no naga
+            let key = self.pipeline_key(PipelineShader::Material(TypeId::of::<M>()), BlendMode::Alpha);
+            match self.create_pipeline(&key, &self.sprite_vertex_source, &self.sprite_fragment_source) {
+                Ok(pipeline) => self.pipelines.insert(key, Some(pipeline)),
+                Err(e) => {
+                    self.materials.remove_type::<M>();
+                    return Err(e.into());
+                }
+            }
+        }
+        self.materials.insert(&self.device, &self.textures, material)
+    }
+
+    // Envia os valores novos do material (uniform e texturas); o handle continua o mesmo
+    pub fn update_material<M: Material>(&mut self, handle: MaterialHandle<M>, material: &M) -> Result<()> {
+        self.materials.update(&self.device, &self.queue, &self.textures, handle, material)
+    }
+
+    // Retorna `false` se o material já tinha sido removido
+    pub fn remove_material(&mut self, material: impl Into<MaterialId>) -> bool {
+        self.materials.remove(material.into())
+    }
+
+    pub fn has_material(&self, material: impl Into<MaterialId>) -> bool {
+        self.materials.contains(material.into())
+    }
+
+    pub fn material_count(&self) -> usize {
+        self.materials.len()
+    }
 }
 
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::graphics::material::Material;
+    use crate::graphics::sprite::Sprite;
+
+    // Render offscreen para os testes; `None` (teste ignorado) em máquinas sem adaptador
+    pub(crate) fn headless() -> Option<Render> {
+        match pollster::block_on(Render::new_headless(64, 64)) {
+            Ok(render) => Some(render),
+            Err(e) => {
+                eprintln!("Sem adaptador para o teste: {}", e);
+                None
+            }
+        }
+    }
+
+    struct Tinted(TextureHandle);
+
+    impl Material for Tinted {
+        type Uniform = [f32; 4];
+        const TEXTURE_COUNT: usize = 1;
+
+        fn fragment_shader() -> &'static str {
+            "@group(2) @binding(1) var extra_sampler: sampler;
+            @group(2) @binding(2) var extra: texture_2d<f32>;
+
+            struct FragmentInput {
+                @location(0) tex_coords: vec2<f32>,
+                @location(1) tint: vec4<f32>,
+            };
+
+            @fragment
+            fn main(input: FragmentInput) -> @location(0) vec4<f32> {
+                return textureSample(extra, extra_sampler, input.tex_coords) * input.tint;
+            }"
+        }
+
+        fn uniform(&self) -> [f32; 4] {
+            [0.0; 4]
+        }
+
+        fn textures(&self) -> Vec<TextureHandle> {
+            vec![self.0]
+        }
+    }
+
+    fn solid(color: [u8; 4]) -> image::RgbaImage {
+        image::RgbaImage::from_pixel(2, 2, image::Rgba(color))
+    }
+
+    #[test]
+    fn textures_used_by_materials_are_not_destroyed() {
+        let Some(mut render) = headless() else {
+            return;
+        };
+        let white = render.create_texture(&solid([255; 4]), "white");
+        let extra = render.create_texture(&solid([0, 255, 0, 255]), "extra");
+        let target = render.create_render_target(RenderTargetSize::Fixed(8, 8), wgpu::FilterMode::Linear).unwrap();
+        let material = render.add_material(&Tinted(extra)).unwrap();
+        let target_material = render.add_material(&Tinted(target.texture())).unwrap();
+
+        assert!(!render.unload_texture(extra));
+        assert!(!render.destroy_render_target(target));
+        assert!(render.render_target_size(target).is_some());
+
+        // O material continua desenhável (com a textura destruída o wgpu entraria em pânico)
+        let mut batch = SpriteBatch::new();
+        batch.push_with_material(material, white, Sprite { scale: [64.0, 64.0], ..Default::default() });
+        render.render_batch(&batch).unwrap();
+        assert_eq!(render.read_pixels().unwrap().get_pixel(32, 32).0, [0, 255, 0, 255]);
+
+        // Sem o material, a textura pode ser liberada
+        assert!(render.remove_material(material));
+        assert!(render.unload_texture(extra));
+        assert!(render.remove_material(target_material));
+        assert!(render.destroy_render_target(target));
+    }
+
+    #[test]
+    fn texture_updates_outside_the_texture_are_rejected() {
+        let Some(mut render) = headless() else {
+            return;
+        };
+        let texture = render.create_texture(&solid([255; 4]), "white");
+        let pixel = image::RgbaImage::new(1, 1);
+        assert!(render.update_texture(texture, 1, 1, &pixel));
+        assert!(!render.update_texture(texture, 2, 0, &pixel));
+        assert!(!render.update_texture(texture, 0, 2, &pixel));
+        // Sem `checked_add`, `u32::MAX + 1` daria a volta para 0 e passaria pela checagem
+        assert!(!render.update_texture(texture, u32::MAX, 0, &pixel));
+        assert!(!render.update_texture(texture, 0, u32::MAX, &pixel));
+        assert!(!render.update_texture(texture, 0, 0, &image::RgbaImage::new(0, 0)));
+    }
+
+    #[test]
+    fn render_target_textures_cannot_be_replaced() {
+        let Some(mut render) = headless() else {
+            return;
+        };
+        let target = render.create_render_target(RenderTargetSize::Fixed(8, 8), wgpu::FilterMode::Linear).unwrap();
+        assert!(!render.replace_texture(target.texture(), &solid([255; 4])));
+        assert_eq!(render.render_target_size(target), Some((8, 8)));
+
+        let texture = render.create_texture(&solid([255; 4]), "white");
+        assert!(render.replace_texture(texture, &image::RgbaImage::new(4, 4)));
+        assert_eq!(render.texture_size(texture), Some((4, 4)));
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+use std::path::Path;
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+// Erro de um shader WGSL: leitura, sintaxe, validação ou criação do pipeline.
+// `Display` mostra `arquivo:linha:coluna: mensagem` seguido do trecho do código apontado.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderError {
+    pub path: String,
+    pub line: Option<u32>,    // 1 = primeira linha
+    pub column: Option<u32>,  // 1 = primeira coluna
+    pub message: String,
+    pub report: String,  // Diagnóstico completo com o trecho do código (vazio se não houver posição)
+}
+
+impl ShaderError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            line: None,
+            column: None,
+            message: message.into(),
+            report: String::new(),
+        }
+    }
+
+    fn at(mut self, location: Option<naga::SourceLocation>, report: String) -> Self {
+        if let Some(location) = location {
+            self.line = Some(location.line_number);
+            self.column = Some(location.line_position);
+        }
+        self.report = report;
+        self
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{}:{}:{}: {}", self.path, line, column, self.message)?,
+            _ => write!(f, "{}: {}", self.path, self.message)?,
+        }
+        if !self.report.is_empty() {
+            write!(f, "\n{}", self.report.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+// Lê um shader do disco e o valida (`validate_wgsl`)
+pub fn load_wgsl(path: &Path, stage: wgpu::ShaderStages, entry_point: &str) -> Result<String, ShaderError> {
+    let name = path.display().to_string();
+    let source = std::fs::read_to_string(path).map_err(|e| ShaderError::new(&name, format!("Erro ao ler o shader: {}", e)))?;
+    validate_wgsl(&source, &name, stage, entry_point)?;
+    Ok(source)
+}
+
+// Analisa e valida o WGSL com o naga (o mesmo que o wgpu usa) e confere se existe o ponto de
+// entrada `entry_point` do estágio `stage`. Detecta antes de criar o pipeline os erros que
+// fariam o wgpu entrar em pânico. `path` só aparece nas mensagens.
+pub fn validate_wgsl(source: &str, path: &str, stage: wgpu::ShaderStages, entry_point: &str) -> Result<(), ShaderError> {
+    let module = naga::front::wgsl::parse_str(source).map_err(|e| {
+        ShaderError::new(path, e.message()).at(e.location(source), e.emit_to_string_with_path(source, path))
+    })?;
+
+    Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .map_err(|e| {
+            ShaderError::new(path, e.as_inner().to_string())
+                .at(e.location(source), e.emit_to_string_with_path(source, path))
+        })?;
+
+    let naga_stage = if stage == wgpu::ShaderStages::VERTEX {
+        naga::ShaderStage::Vertex
+    } else if stage == wgpu::ShaderStages::FRAGMENT {
+        naga::ShaderStage::Fragment
+    } else {
+        naga::ShaderStage::Compute
+    };
+    if !module.entry_points.iter().any(|entry| entry.name == entry_point && entry.stage == naga_stage) {
+        return Err(ShaderError::new(
+            path,
+            format!("Ponto de entrada `{}` ({:?}) não encontrado", entry_point, naga_stage),
+        ));
+    }
+    Ok(())
+}
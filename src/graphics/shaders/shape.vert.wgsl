@@ -0,0 +1,25 @@
+// shape.vert.wgsl
+
+// Matriz view-projection da Camera2D (a mesma usada pelos sprites)
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn main(input: VertexInput) -> VertexOutput {
+    var output: VertexOutput;
+    output.position = camera.view_proj * vec4<f32>(input.position, 0.0, 1.0); // Mundo para clip space
+    output.color = input.color;
+    return output;
+}
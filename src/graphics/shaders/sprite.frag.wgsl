@@ -5,9 +5,10 @@
 
 struct FragmentInput {
     @location(0) tex_coords: vec2<f32>,
+    @location(1) tint: vec4<f32>,
 };
 
 @fragment
 fn main(input: FragmentInput) -> @location(0) vec4<f32> {
-    return textureSample(my_texture, my_sampler, input.tex_coords); // Aplica a textura usando UVs
+    return textureSample(my_texture, my_sampler, input.tex_coords) * input.tint; // Aplica a textura usando UVs e a cor do sprite
 }
@@ -1,19 +1,42 @@
 // sprite.vert.wgsl
 
+// Matriz view-projection da Camera2D
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(1) @binding(0) var<uniform> camera: CameraUniform;
+
 struct VertexInput {
     @location(0) position: vec2<f32>,
     @location(1) tex_coords: vec2<f32>,
 };
 
+// Atributos por instância (um por sprite do SpriteBatch)
+struct InstanceInput {
+    @location(2) position: vec2<f32>,
+    @location(3) scale: vec2<f32>,
+    @location(4) rotation: f32,
+    @location(5) uv_rect: vec4<f32>,
+    @location(6) tint: vec4<f32>,
+};
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
+    @location(1) tint: vec4<f32>,
 };
 
 @vertex
-fn main(input: VertexInput) -> VertexOutput {
+fn main(input: VertexInput, instance: InstanceInput) -> VertexOutput {
+    // Escala, rotaciona e translada o quad unitário
+    let scaled = input.position * instance.scale;
+    let c = cos(instance.rotation);
+    let s = sin(instance.rotation);
+    let rotated = vec2<f32>(scaled.x * c - scaled.y * s, scaled.x * s + scaled.y * c);
+
     var output: VertexOutput;
-    output.position = vec4<f32>(input.position, 0.0, 1.0); // Posição 2D convertida para 4D
-    output.tex_coords = input.tex_coords;
+    output.position = camera.view_proj * vec4<f32>(rotated + instance.position, 0.0, 1.0); // Mundo para clip space
+    output.tex_coords = instance.uv_rect.xy + input.tex_coords * instance.uv_rect.zw; // UV dentro da região do sprite
+    output.tint = instance.tint;
     return output;
 }
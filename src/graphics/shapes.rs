@@ -0,0 +1,340 @@
+use bytemuck::{Pod, Zeroable};
+use glam::Vec2;
+
+// Capacidade inicial dos buffers de geometria (crescem conforme a demanda)
+const INITIAL_VERTEX_CAPACITY: usize = 4096;
+const INITIAL_INDEX_CAPACITY: usize = 8192;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct ShapeVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+impl ShapeVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![
+        0 => Float32x2,  // position
+        1 => Float32x4,  // color
+    ];
+
+    fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ShapeVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// Desenho imediato de formas 2D com cor sólida (debug e geometria de gameplay).
+// As formas são transformadas em triângulos na CPU a cada chamada, enviadas à GPU
+// no próximo frame do `Render` e descartadas em seguida. Coordenadas em unidades do mundo.
+pub struct ShapeRenderer {
+    vertices: Vec<ShapeVertex>,
+    indices: Vec<u32>,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    vertex_capacity: usize,
+    index_capacity: usize,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl ShapeRenderer {
+    pub(crate) fn new(device: &wgpu::Device, camera_layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        // Carregar os shaders(Wgsl)
+        let vertex_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shape Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/shape.vert.wgsl").into()),
+        });
+        let fragment_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shape Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/shape.frag.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shape Pipeline Layout"),
+            bind_group_layouts: &[camera_layout],  // Só a câmera, não há textura
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shape Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &vertex_shader_module,
+                entry_point: "main",
+                buffers: &[ShapeVertex::layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &fragment_shader_module,
+                entry_point: "main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,  // A ordem dos vértices depende da forma
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,  // Mesmo MSAA do pipeline de sprites (mesmo render pass)
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            vertex_buffer: Self::create_buffer(device, "Shape Vertex Buffer", INITIAL_VERTEX_CAPACITY * std::mem::size_of::<ShapeVertex>(), wgpu::BufferUsages::VERTEX),
+            index_buffer: Self::create_buffer(device, "Shape Index Buffer", INITIAL_INDEX_CAPACITY * std::mem::size_of::<u32>(), wgpu::BufferUsages::INDEX),
+            vertex_capacity: INITIAL_VERTEX_CAPACITY,
+            index_capacity: INITIAL_INDEX_CAPACITY,
+            pipeline,
+        }
+    }
+
+    fn create_buffer(device: &wgpu::Device, label: &str, size: usize, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: size as wgpu::BufferAddress,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    // Descarta as formas acumuladas sem desenhar
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+    }
+
+    pub fn line(&mut self, from: [f32; 2], to: [f32; 2], thickness: f32, color: [f32; 4]) {
+        self.polyline(&[from, to], thickness, color, false);
+    }
+
+    // Linha com várias partes; `closed` liga o último ponto ao primeiro.
+    // As junções usam miter (limitado para ângulos muito fechados).
+    pub fn polyline(&mut self, points: &[[f32; 2]], thickness: f32, color: [f32; 4], closed: bool) {
+        let count = points.len();
+        if count < 2 {
+            return;
+        }
+        let half = thickness * 0.5;
+        let point = |i: usize| Vec2::from(points[i]);
+
+        let base = self.vertices.len() as u32;
+        for i in 0..count {
+            let previous = if i > 0 { Some(i - 1) } else if closed { Some(count - 1) } else { None };
+            let next = if i + 1 < count { Some(i + 1) } else if closed { Some(0) } else { None };
+
+            let normal_in = previous.map(|p| (point(i) - point(p)).normalize_or_zero().perp());
+            let normal_out = next.map(|n| (point(n) - point(i)).normalize_or_zero().perp());
+            let offset = match (normal_in, normal_out) {
+                (Some(a), Some(b)) => {
+                    let miter = (a + b).normalize_or_zero();
+                    if miter == Vec2::ZERO {
+                        b * half
+                    } else {
+                        // Comprimento do miter, limitado a 4x a meia espessura
+                        miter * (half / miter.dot(b).max(0.25))
+                    }
+                }
+                (Some(normal), None) | (None, Some(normal)) => normal * half,
+                (None, None) => Vec2::ZERO,
+            };
+
+            self.push_vertex(point(i) + offset, color);
+            self.push_vertex(point(i) - offset, color);
+        }
+
+        let segments = if closed { count } else { count - 1 };
+        for i in 0..segments as u32 {
+            let a = base + i * 2;
+            let b = base + ((i + 1) % count as u32) * 2;
+            self.indices.extend_from_slice(&[a, a + 1, b, b, a + 1, b + 1]);
+        }
+    }
+
+    // Polígono convexo preenchido (triangulado em leque a partir do primeiro ponto)
+    pub fn polygon(&mut self, points: &[[f32; 2]], color: [f32; 4]) {
+        if points.len() < 3 {
+            return;
+        }
+        let base = self.vertices.len() as u32;
+        for point in points {
+            self.push_vertex(Vec2::from(*point), color);
+        }
+        for i in 1..points.len() as u32 - 1 {
+            self.indices.extend_from_slice(&[base, base + i, base + i + 1]);
+        }
+    }
+
+    pub fn polygon_outline(&mut self, points: &[[f32; 2]], thickness: f32, color: [f32; 4]) {
+        self.polyline(points, thickness, color, true);
+    }
+
+    // Retângulo centrado em `center`, como os sprites
+    pub fn rect(&mut self, center: [f32; 2], size: [f32; 2], color: [f32; 4]) {
+        self.polygon(&rect_corners(center, size), color);
+    }
+
+    pub fn rect_outline(&mut self, center: [f32; 2], size: [f32; 2], thickness: f32, color: [f32; 4]) {
+        self.polyline(&rect_corners(center, size), thickness, color, true);
+    }
+
+    pub fn circle(&mut self, center: [f32; 2], radius: f32, color: [f32; 4]) {
+        let points = arc_points(center, radius, 0.0, std::f32::consts::TAU, false);
+        self.polygon(&points, color);
+    }
+
+    pub fn circle_outline(&mut self, center: [f32; 2], radius: f32, thickness: f32, color: [f32; 4]) {
+        let points = arc_points(center, radius, 0.0, std::f32::consts::TAU, false);
+        self.polyline(&points, thickness, color, true);
+    }
+
+    // Arco de circunferência entre dois ângulos (radianos, anti-horário a partir do eixo X)
+    pub fn arc(&mut self, center: [f32; 2], radius: f32, start_angle: f32, end_angle: f32, thickness: f32, color: [f32; 4]) {
+        let points = arc_points(center, radius, start_angle, end_angle, true);
+        self.polyline(&points, thickness, color, false);
+    }
+
+    fn push_vertex(&mut self, position: Vec2, color: [f32; 4]) {
+        self.vertices.push(ShapeVertex {
+            position: position.into(),
+            color,
+        });
+    }
+
+    // Envia a geometria do frame para a GPU, aumentando os buffers se preciso
+    pub(crate) fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if self.indices.is_empty() {
+            return;
+        }
+        if self.vertices.len() > self.vertex_capacity {
+            self.vertex_capacity = self.vertices.len().next_power_of_two();
+            self.vertex_buffer = Self::create_buffer(device, "Shape Vertex Buffer", self.vertex_capacity * std::mem::size_of::<ShapeVertex>(), wgpu::BufferUsages::VERTEX);
+        }
+        if self.indices.len() > self.index_capacity {
+            self.index_capacity = self.indices.len().next_power_of_two();
+            self.index_buffer = Self::create_buffer(device, "Shape Index Buffer", self.index_capacity * std::mem::size_of::<u32>(), wgpu::BufferUsages::INDEX);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
+        queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&self.indices));
+    }
+
+    pub(crate) fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, camera_bind_group: &'a wgpu::BindGroup) {
+        if self.indices.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..self.indices.len() as u32, 0, 0..1);
+    }
+}
+
+fn rect_corners(center: [f32; 2], size: [f32; 2]) -> [[f32; 2]; 4] {
+    let (hx, hy) = (size[0] * 0.5, size[1] * 0.5);
+    let [x, y] = center;
+    [[x - hx, y - hy], [x + hx, y - hy], [x + hx, y + hy], [x - hx, y + hy]]
+}
+
+// Pontos sobre a circunferência; o número de segmentos cresce com o raio
+fn arc_points(center: [f32; 2], radius: f32, start_angle: f32, end_angle: f32, include_end: bool) -> Vec<[f32; 2]> {
+    let sweep = end_angle - start_angle;
+    let full_segments = ((radius.abs().sqrt() * 6.0) as u32).clamp(12, 128);
+    let segments = ((full_segments as f32 * sweep.abs() / std::f32::consts::TAU).ceil() as u32).max(1);
+    let count = if include_end { segments + 1 } else { segments };
+
+    (0..count)
+        .map(|i| {
+            let angle = start_angle + sweep * i as f32 / segments as f32;
+            [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::render::tests::headless;
+    use crate::graphics::sprite::SpriteBatch;
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+    #[test]
+    fn rect_corners_are_counter_clockwise_around_the_center() {
+        assert_eq!(rect_corners([1.0, 2.0], [4.0, 2.0]), [[-1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [-1.0, 3.0]]);
+    }
+
+    #[test]
+    fn arc_points_cover_the_sweep() {
+        // Círculo pequeno: o mínimo de 12 segmentos, sem repetir o ponto inicial
+        let circle = arc_points([0.0, 0.0], 1.0, 0.0, std::f32::consts::TAU, false);
+        assert_eq!(circle.len(), 12);
+        assert_eq!(circle[0], [1.0, 0.0]);
+
+        // Um quarto de círculo grande, incluindo a ponta final
+        let arc = arc_points([0.0, 0.0], 10_000.0, 0.0, std::f32::consts::FRAC_PI_2, true);
+        assert_eq!(arc.len(), 32 + 1);
+        let [x, y] = arc[arc.len() - 1];
+        assert!(x.abs() < 0.01 && (y - 10_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn shapes_are_triangulated_on_the_cpu() {
+        let Some(mut render) = headless() else {
+            return;
+        };
+        let shapes = render.shapes_mut();
+        assert!(shapes.is_empty());
+
+        // Entradas degeneradas não geram geometria
+        shapes.polygon(&[[0.0, 0.0], [1.0, 0.0]], RED);
+        shapes.polyline(&[[0.0, 0.0]], 1.0, RED, false);
+        assert!(shapes.is_empty());
+
+        shapes.rect([0.0, 0.0], [2.0, 2.0], RED);
+        assert_eq!((shapes.vertices.len(), shapes.indices.len()), (4, 6));
+
+        // Uma linha vira um quad com a espessura perpendicular a ela
+        shapes.clear();
+        shapes.line([0.0, 0.0], [10.0, 0.0], 2.0, RED);
+        let positions: Vec<[f32; 2]> = shapes.vertices.iter().map(|v| v.position).collect();
+        assert_eq!(positions, [[0.0, 1.0], [0.0, -1.0], [10.0, 1.0], [10.0, -1.0]]);
+
+        // Contorno fechado: dois vértices por ponto e um segmento a mais ligando o fim ao início
+        shapes.clear();
+        shapes.rect_outline([0.0, 0.0], [2.0, 2.0], 0.5, RED);
+        assert_eq!((shapes.vertices.len(), shapes.indices.len()), (8, 24));
+        assert!(shapes.indices.iter().all(|&i| i < 8));
+        // Os cantos usam miter: os dois vértices do canto ficam na diagonal, a meia espessura de cada lado
+        let corner: Vec<[f32; 2]> = shapes.vertices[..2].iter().map(|v| v.position).collect();
+        assert_eq!(corner, [[-0.75, -0.75], [-1.25, -1.25]]);
+    }
+
+    #[test]
+    fn shapes_are_drawn_over_the_batch_and_discarded() {
+        let Some(mut render) = headless() else {
+            return;
+        };
+        render.shapes_mut().rect([0.0, 0.0], [16.0, 16.0], RED);
+        render.render_batch(&SpriteBatch::new()).unwrap();
+
+        let pixels = render.read_pixels().unwrap();
+        assert_eq!(pixels.get_pixel(32, 32).0, [255, 0, 0, 255]);
+        assert_eq!(pixels.get_pixel(2, 2).0[0], 0);
+        assert!(render.shapes().is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use std::ops::Range;
+
+use bytemuck::{Pod, Zeroable};
+
+use super::material::MaterialId;
+use super::render::BlendMode;
+use super::texture::TextureHandle;
+
+// Um sprite a ser desenhado no frame atual
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprite {
+    pub position: [f32; 2],  // Centro do sprite, em unidades do mundo
+    pub rotation: f32,       // Rotação em radianos (anti-horário)
+    pub scale: [f32; 2],     // Tamanho do quad em unidades do mundo (escala negativa espelha o sprite)
+    pub uv_rect: [f32; 4],   // Região da textura: x, y, largura, altura (0..1)
+    pub tint: [f32; 4],      // Cor multiplicada pela textura (RGBA)
+    pub layer: f32,          // Camadas maiores são desenhadas por cima
+    pub blend: BlendMode,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 0.0],
+            rotation: 0.0,
+            scale: [1.0, 1.0],
+            uv_rect: [0.0, 0.0, 1.0, 1.0],
+            tint: [1.0, 1.0, 1.0, 1.0],
+            layer: 0.0,
+            blend: BlendMode::Alpha,
+        }
+    }
+}
+
+// Dados de um sprite como são enviados para o instance buffer
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub(crate) struct SpriteInstance {
+    position: [f32; 2],
+    scale: [f32; 2],
+    rotation: f32,
+    uv_rect: [f32; 4],
+    tint: [f32; 4],
+}
+
+impl SpriteInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        2 => Float32x2,  // position
+        3 => Float32x2,  // scale
+        4 => Float32,    // rotation
+        5 => Float32x4,  // uv_rect
+        6 => Float32x4,  // tint
+    ];
+
+    pub(crate) fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl From<&Sprite> for SpriteInstance {
+    fn from(sprite: &Sprite) -> Self {
+        Self {
+            position: sprite.position,
+            scale: sprite.scale,
+            rotation: sprite.rotation,
+            uv_rect: sprite.uv_rect,
+            tint: sprite.tint,
+        }
+    }
+}
+
+// Uma chamada de draw instanciada: um intervalo do instance buffer com a mesma textura, material e blend
+pub(crate) struct DrawCall {
+    pub material: Option<MaterialId>,  // `None` = pipeline padrão dos sprites
+    pub blend: BlendMode,
+    pub texture: TextureHandle,
+    pub instances: Range<u32>,
+}
+
+// Lista de sprites acumulados durante o frame e desenhados de uma vez com `Render::render_batch`
+#[derive(Default)]
+pub struct SpriteBatch {
+    entries: Vec<(Option<MaterialId>, TextureHandle, Sprite)>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, texture: TextureHandle, sprite: Sprite) {
+        self.entries.push((None, texture, sprite));
+    }
+
+    // Sprite desenhado com um material (`Render::add_material`) em vez do shader padrão
+    pub fn push_with_material(&mut self, material: impl Into<MaterialId>, texture: TextureHandle, sprite: Sprite) {
+        self.entries.push((Some(material.into()), texture, sprite));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Ordena os sprites por camada e, dentro da camada, por blend, material e textura, para que
+    // sprites consecutivos com o mesmo estado virem uma única chamada de draw
+    pub(crate) fn prepare(&self) -> (Vec<SpriteInstance>, Vec<DrawCall>) {
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (material_a, texture_a, sprite_a) = &self.entries[a];
+            let (material_b, texture_b, sprite_b) = &self.entries[b];
+            sprite_a
+                .layer
+                .total_cmp(&sprite_b.layer)
+                .then_with(|| sprite_a.blend.cmp(&sprite_b.blend))
+                .then_with(|| material_a.cmp(material_b))
+                .then_with(|| texture_a.cmp(texture_b))
+        });
+
+        let mut instances = Vec::with_capacity(order.len());
+        let mut draws: Vec<DrawCall> = Vec::new();
+        for index in order {
+            let (material, texture, sprite) = &self.entries[index];
+            let instance = instances.len() as u32;
+            instances.push(SpriteInstance::from(sprite));
+
+            match draws.last_mut() {
+                Some(draw) if draw.material == *material && draw.blend == sprite.blend && draw.texture == *texture => {
+                    draw.instances.end = instance + 1
+                }
+                _ => draws.push(DrawCall {
+                    material: *material,
+                    blend: sprite.blend,
+                    texture: *texture,
+                    instances: instance..instance + 1,
+                }),
+            }
+        }
+
+        (instances, draws)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(layer: f32, blend: BlendMode, x: f32) -> Sprite {
+        Sprite { position: [x, 0.0], layer, blend, ..Default::default() }
+    }
+
+    // (material, blend, textura, intervalo) de cada chamada de draw
+    fn summary(draws: &[DrawCall]) -> Vec<(Option<MaterialId>, BlendMode, TextureHandle, Range<u32>)> {
+        draws.iter().map(|d| (d.material, d.blend, d.texture, d.instances.clone())).collect()
+    }
+
+    #[test]
+    fn empty_batch_has_no_draws() {
+        let (instances, draws) = SpriteBatch::new().prepare();
+        assert!(instances.is_empty() && draws.is_empty());
+    }
+
+    #[test]
+    fn sprites_with_the_same_state_merge_into_one_draw() {
+        let (a, b) = (TextureHandle::for_test(0), TextureHandle::for_test(1));
+        let mut batch = SpriteBatch::new();
+        batch.push(b, sprite(0.0, BlendMode::Alpha, 0.0));
+        batch.push(a, sprite(0.0, BlendMode::Alpha, 1.0));
+        batch.push(b, sprite(0.0, BlendMode::Alpha, 2.0));
+        batch.push(a, sprite(0.0, BlendMode::Alpha, 3.0));
+
+        let (instances, draws) = batch.prepare();
+        assert_eq!(summary(&draws), [(None, BlendMode::Alpha, a, 0..2), (None, BlendMode::Alpha, b, 2..4)]);
+        // A ordenação é estável: dentro de um draw os sprites mantêm a ordem de inserção
+        let xs: Vec<f32> = instances.iter().map(|i| i.position[0]).collect();
+        assert_eq!(xs, [1.0, 3.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn layers_come_before_blend_material_and_texture() {
+        let (a, b) = (TextureHandle::for_test(0), TextureHandle::for_test(1));
+        let material = MaterialId::for_test(0);
+        let mut batch = SpriteBatch::new();
+        batch.push(a, sprite(1.0, BlendMode::Alpha, 0.0));
+        batch.push(b, sprite(0.0, BlendMode::Additive, 1.0));
+        batch.push(a, sprite(0.0, BlendMode::Alpha, 2.0));
+        batch.push_with_material(material, a, sprite(0.0, BlendMode::Alpha, 3.0));
+        batch.push(a, sprite(-1.0, BlendMode::Alpha, 4.0));
+        assert_eq!(batch.len(), 5);
+
+        let (instances, draws) = batch.prepare();
+        assert_eq!(
+            summary(&draws),
+            [
+                // Camadas diferentes com o mesmo estado continuam num draw só: a ordem das instâncias já as separa
+                (None, BlendMode::Alpha, a, 0..2),
+                (Some(material), BlendMode::Alpha, a, 2..3),
+                (None, BlendMode::Additive, b, 3..4),
+                (None, BlendMode::Alpha, a, 4..5),
+            ]
+        );
+        let xs: Vec<f32> = instances.iter().map(|i| i.position[0]).collect();
+        assert_eq!(xs, [4.0, 2.0, 3.0, 1.0, 0.0]);
+
+        batch.clear();
+        assert!(batch.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+// Render targets: texturas em que se desenha um `SpriteBatch` (minimapa, portais, pixel art
+// desenhada em baixa resolução e ampliada) e que depois entram no batch como qualquer textura.
+
+use std::collections::HashMap;
+
+use super::texture::TextureHandle;
+
+// Formato das texturas dos render targets (mesma ordem de bytes do `image::RgbaImage`)
+pub(crate) const RENDER_TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RenderTargetSize {
+    Fixed(u32, u32),  // Largura e altura em pixels
+    Window(f32),      // Fração do tamanho da janela (1.0 = mesmo tamanho); acompanha os redimensionamentos
+}
+
+impl RenderTargetSize {
+    // Tamanho em pixels para uma janela com o tamanho `window` (no mínimo 1x1 em `Window`)
+    pub fn resolve(self, window: (u32, u32)) -> (u32, u32) {
+        match self {
+            RenderTargetSize::Fixed(width, height) => (width, height),
+            RenderTargetSize::Window(scale) => (
+                ((window.0 as f32 * scale).round() as u32).max(1),
+                ((window.1 as f32 * scale).round() as u32).max(1),
+            ),
+        }
+    }
+}
+
+// Um render target criado no `Render`. A textura dele pode ser desenhada como sprite
+// (`batch.push(target.texture(), ...)`), inclusive no frame em que foi preenchida.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RenderTarget {
+    texture: TextureHandle,
+}
+
+impl RenderTarget {
+    pub fn texture(self) -> TextureHandle {
+        self.texture
+    }
+}
+
+impl From<RenderTarget> for TextureHandle {
+    fn from(target: RenderTarget) -> Self {
+        target.texture
+    }
+}
+
+// Como recriar a textura de um render target quando o tamanho muda
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct TargetInfo {
+    pub(crate) size: RenderTargetSize,
+    pub(crate) filter: wgpu::FilterMode,  // Amostragem quando desenhado como sprite
+}
+
+// Render targets do `Render`; as texturas ficam no `TextureStore`
+#[derive(Default)]
+pub(crate) struct RenderTargets {
+    targets: HashMap<TextureHandle, TargetInfo>,
+}
+
+impl RenderTargets {
+    pub(crate) fn insert(&mut self, texture: TextureHandle, info: TargetInfo) -> RenderTarget {
+        self.targets.insert(texture, info);
+        RenderTarget { texture }
+    }
+
+    pub(crate) fn get(&self, target: RenderTarget) -> Option<&TargetInfo> {
+        self.targets.get(&target.texture)
+    }
+
+    // Se a textura pertence a um render target (o handle foi obtido com `RenderTarget::texture`)
+    pub(crate) fn contains_texture(&self, texture: TextureHandle) -> bool {
+        self.targets.contains_key(&texture)
+    }
+
+    pub(crate) fn get_mut(&mut self, target: RenderTarget) -> Option<&mut TargetInfo> {
+        self.targets.get_mut(&target.texture)
+    }
+
+    pub(crate) fn remove(&mut self, target: RenderTarget) -> bool {
+        self.targets.remove(&target.texture).is_some()
+    }
+
+    // Render targets que acompanham o tamanho da janela
+    pub(crate) fn window_sized(&self) -> Vec<(RenderTarget, TargetInfo)> {
+        self.targets
+            .iter()
+            .filter(|(_, info)| matches!(info.size, RenderTargetSize::Window(_)))
+            .map(|(texture, info)| (RenderTarget { texture: *texture }, *info))
+            .collect()
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::bmfont::BmFontDescriptor;
+use super::render::Render;
+use super::sprite::{Sprite, SpriteBatch};
+use super::texture::TextureHandle;
+
+// Tamanho das páginas do cache de glifos das fontes TrueType
+const GLYPH_PAGE_SIZE: u32 = 1024;
+// Espaço entre glifos no cache (evita que o filtro linear pegue o vizinho)
+const GLYPH_PADDING: u32 = 1;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    pub size: f32,               // Altura da fonte em unidades do mundo (pixels com zoom 1)
+    pub color: [f32; 4],
+    pub align: TextAlign,
+    pub max_width: Option<f32>,  // Quebra as linhas entre palavras ao passar desta largura
+    pub line_spacing: f32,       // Multiplicador da altura de linha
+    pub layer: f32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            size: 16.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            align: TextAlign::Left,
+            max_width: None,
+            line_spacing: 1.0,
+            layer: 0.0,
+        }
+    }
+}
+
+// Glifo posicionado pelo layout, relativo ao canto superior esquerdo do texto (Y para baixo)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PositionedGlyph {
+    pub ch: char,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub width: f32,
+    pub height: f32,
+}
+
+enum Font {
+    TrueType(fontdue::Font),
+    Bitmap {
+        descriptor: BmFontDescriptor,
+        pages: Vec<TextureHandle>,
+    },
+}
+
+// Medidas de um glifo já na escala pedida
+#[derive(Copy, Clone, Default)]
+struct GlyphMetrics {
+    width: f32,
+    height: f32,
+    offset_x: f32,  // Da posição da caneta até a borda esquerda do glifo
+    offset_y: f32,  // Da linha de base até o topo do glifo (Y para baixo)
+    advance: f32,
+}
+
+// Onde um glifo rasterizado ficou no cache
+#[derive(Copy, Clone)]
+struct CachedGlyph {
+    texture: TextureHandle,
+    uv_rect: [f32; 4],
+}
+
+// Atlas dinâmico dos glifos TrueType, preenchido à medida que os textos pedem
+#[derive(Default)]
+struct GlyphCache {
+    pages: Vec<TextureHandle>,
+    cursor_x: u32,
+    cursor_y: u32,
+    shelf_height: u32,
+    glyphs: HashMap<(usize, char, u32), Option<CachedGlyph>>,
+}
+
+impl GlyphCache {
+    // Reserva espaço para um glifo, abrindo uma página nova quando a atual enche
+    fn allocate(&mut self, render: &mut Render, width: u32, height: u32) -> Option<(TextureHandle, u32, u32)> {
+        if width + 2 * GLYPH_PADDING > GLYPH_PAGE_SIZE || height + 2 * GLYPH_PADDING > GLYPH_PAGE_SIZE {
+            return None;
+        }
+
+        if self.cursor_x + width + GLYPH_PADDING > GLYPH_PAGE_SIZE {
+            self.cursor_x = GLYPH_PADDING;
+            self.cursor_y += self.shelf_height + GLYPH_PADDING;
+            self.shelf_height = 0;
+        }
+        if self.pages.is_empty() || self.cursor_y + height + GLYPH_PADDING > GLYPH_PAGE_SIZE {
+            let page = image::RgbaImage::new(GLYPH_PAGE_SIZE, GLYPH_PAGE_SIZE);
+            self.pages.push(render.create_texture(&page, "glyph_cache"));
+            self.cursor_x = GLYPH_PADDING;
+            self.cursor_y = GLYPH_PADDING;
+            self.shelf_height = 0;
+        }
+
+        let position = (*self.pages.last()?, self.cursor_x, self.cursor_y);
+        self.cursor_x += width + GLYPH_PADDING;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(position)
+    }
+}
+
+// Subsistema de texto: carrega fontes TrueType/OpenType e BMFont, faz o layout
+// (kerning, quebra de linha, alinhamento) e desenha os glifos pelo SpriteBatch
+#[derive(Default)]
+pub struct TextRenderer {
+    fonts: Vec<Font>,
+    sources: Vec<Option<PathBuf>>,  // Arquivo de cada fonte (caminho canônico), para recarregar
+    cache: GlyphCache,
+}
+
+impl TextRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load_ttf(&mut self, path: impl AsRef<Path>) -> Result<FontHandle> {
+        let path = path.as_ref();
+        let font = read_ttf(path)?;
+        Ok(self.push(font, Some(path)))
+    }
+
+    pub fn add_ttf_bytes(&mut self, bytes: &[u8]) -> Result<FontHandle> {
+        let font = parse_ttf(bytes)?;
+        Ok(self.push(font, None))
+    }
+
+    // Carrega uma fonte bitmap (.fnt em formato texto) e as texturas das suas páginas
+    pub fn load_bmfont(&mut self, render: &mut Render, path: impl AsRef<Path>) -> Result<FontHandle> {
+        let path = path.as_ref();
+        let font = read_bmfont(render, path)?;
+        Ok(self.push(font, Some(path)))
+    }
+
+    // Relê as fontes carregadas de arquivos que mudaram (`AssetServer::changed_files`),
+    // mantendo os handles. As páginas das BMFont são recarregadas pelo próprio `AssetServer`.
+    pub fn reload_changed(&mut self, render: &mut Render, changed: &[PathBuf]) -> Result<()> {
+        for index in 0..self.fonts.len() {
+            let Some(path) = self.sources[index].as_ref().filter(|path| changed.contains(path)) else {
+                continue;
+            };
+            self.fonts[index] = match self.fonts[index] {
+                Font::TrueType(_) => read_ttf(path)?,
+                Font::Bitmap { .. } => read_bmfont(render, path)?,
+            };
+            // Os glifos antigos continuam ocupando espaço nas páginas do cache, só não são mais usados
+            self.cache.glyphs.retain(|&(font, _, _), _| font != index);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, font: Font, path: Option<&Path>) -> FontHandle {
+        self.fonts.push(font);
+        self.sources
+            .push(path.map(|path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())));
+        FontHandle(self.fonts.len() - 1)
+    }
+
+    pub fn measure(&self, font: FontHandle, text: &str, style: &TextStyle) -> [f32; 2] {
+        let layout = self.layout(font, text, style);
+        [layout.width, layout.height]
+    }
+
+    // Posiciona os glifos sem tocar na GPU
+    pub fn layout(&self, font: FontHandle, text: &str, style: &TextStyle) -> TextLayout {
+        let Some(font) = self.fonts.get(font.0) else {
+            return TextLayout::default();
+        };
+        let size = style.size;
+        let (ascent, line_height) = line_metrics(font, size);
+        let line_height = line_height * style.line_spacing;
+
+        // Quebra em linhas: primeiro pelos '\n', depois entre palavras se houver largura máxima
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+                let too_wide = style
+                    .max_width
+                    .is_some_and(|max| measure_line(font, &candidate, size) > max);
+                if too_wide && !line.is_empty() {
+                    lines.push(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+
+        let widths: Vec<f32> = lines.iter().map(|line| measure_line(font, line, size)).collect();
+        let block_width = style
+            .max_width
+            .unwrap_or_else(|| widths.iter().copied().fold(0.0, f32::max));
+
+        let mut layout = TextLayout {
+            glyphs: Vec::new(),
+            width: widths.iter().copied().fold(0.0, f32::max),
+            height: line_height * lines.len() as f32,
+        };
+
+        for (index, (line, width)) in lines.iter().zip(&widths).enumerate() {
+            let baseline = index as f32 * line_height + ascent;
+            let mut pen_x = match style.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (block_width - width) * 0.5,
+                TextAlign::Right => block_width - width,
+            };
+
+            let mut previous = None;
+            for ch in line.chars() {
+                if let Some(previous) = previous {
+                    pen_x += kerning(font, previous, ch, size);
+                }
+                let metrics = glyph_metrics(font, ch, size);
+                if metrics.width > 0.0 && metrics.height > 0.0 {
+                    layout.glyphs.push(PositionedGlyph {
+                        ch,
+                        x: pen_x + metrics.offset_x,
+                        y: baseline + metrics.offset_y,
+                        width: metrics.width,
+                        height: metrics.height,
+                    });
+                }
+                pen_x += metrics.advance;
+                previous = Some(ch);
+            }
+        }
+
+        layout
+    }
+
+    // Adiciona o texto ao batch. `position` é o canto superior esquerdo do bloco, em unidades do mundo.
+    pub fn draw(
+        &mut self,
+        render: &mut Render,
+        batch: &mut SpriteBatch,
+        font: FontHandle,
+        text: &str,
+        position: [f32; 2],
+        style: &TextStyle,
+    ) {
+        let layout = self.layout(font, text, style);
+        for glyph in &layout.glyphs {
+            let Some((texture, uv_rect)) = self.glyph_texture(render, font, glyph.ch, style.size) else {
+                continue;
+            };
+            batch.push(
+                texture,
+                Sprite {
+                    position: [
+                        position[0] + glyph.x + glyph.width * 0.5,
+                        position[1] - glyph.y - glyph.height * 0.5,
+                    ],
+                    scale: [glyph.width, glyph.height],
+                    uv_rect,
+                    tint: style.color,
+                    layer: style.layer,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    // Textura e UV do glifo; glifos TrueType são rasterizados e enviados ao cache na primeira vez
+    fn glyph_texture(&mut self, render: &mut Render, font: FontHandle, ch: char, size: f32) -> Option<(TextureHandle, [f32; 4])> {
+        match self.fonts.get(font.0)? {
+            Font::Bitmap { descriptor, pages } => {
+                let glyph = descriptor.chars.get(&ch)?;
+                let uv_rect = [
+                    glyph.x as f32 / descriptor.scale_w as f32,
+                    glyph.y as f32 / descriptor.scale_h as f32,
+                    glyph.width as f32 / descriptor.scale_w as f32,
+                    glyph.height as f32 / descriptor.scale_h as f32,
+                ];
+                Some((*pages.get(glyph.page)?, uv_rect))
+            }
+            Font::TrueType(ttf) => {
+                let key = (font.0, ch, size.to_bits());
+                if let Some(cached) = self.cache.glyphs.get(&key) {
+                    return cached.map(|glyph| (glyph.texture, glyph.uv_rect));
+                }
+
+                let (metrics, coverage) = ttf.rasterize(ch, size);
+                let (width, height) = (metrics.width as u32, metrics.height as u32);
+                let cached = if width == 0 || height == 0 {
+                    None
+                } else {
+                    // Glifo branco com a cobertura no alfa; a cor vem do tint do sprite
+                    let img = image::RgbaImage::from_fn(width, height, |x, y| {
+                        image::Rgba([255, 255, 255, coverage[(y * width + x) as usize]])
+                    });
+                    self.cache.allocate(render, width, height).map(|(texture, x, y)| {
+                        render.update_texture(texture, x, y, &img);
+                        CachedGlyph {
+                            texture,
+                            uv_rect: [
+                                x as f32 / GLYPH_PAGE_SIZE as f32,
+                                y as f32 / GLYPH_PAGE_SIZE as f32,
+                                width as f32 / GLYPH_PAGE_SIZE as f32,
+                                height as f32 / GLYPH_PAGE_SIZE as f32,
+                            ],
+                        }
+                    })
+                };
+                self.cache.glyphs.insert(key, cached);
+                cached.map(|glyph| (glyph.texture, glyph.uv_rect))
+            }
+        }
+    }
+}
+
+// (ascent, altura da linha) na escala pedida
+fn line_metrics(font: &Font, size: f32) -> (f32, f32) {
+    match font {
+        Font::TrueType(ttf) => match ttf.horizontal_line_metrics(size) {
+            Some(metrics) => (metrics.ascent, metrics.new_line_size),
+            None => (size * 0.8, size * 1.2),
+        },
+        Font::Bitmap { descriptor, .. } => {
+            let scale = size / descriptor.size;
+            (descriptor.base * scale, descriptor.line_height * scale)
+        }
+    }
+}
+
+fn glyph_metrics(font: &Font, ch: char, size: f32) -> GlyphMetrics {
+    match font {
+        Font::TrueType(ttf) => {
+            let metrics = ttf.metrics(ch, size);
+            GlyphMetrics {
+                width: metrics.width as f32,
+                height: metrics.height as f32,
+                offset_x: metrics.xmin as f32,
+                offset_y: -(metrics.ymin as f32 + metrics.height as f32),
+                advance: metrics.advance_width,
+            }
+        }
+        Font::Bitmap { descriptor, .. } => {
+            let scale = size / descriptor.size;
+            match descriptor.chars.get(&ch) {
+                Some(glyph) => GlyphMetrics {
+                    width: glyph.width as f32 * scale,
+                    height: glyph.height as f32 * scale,
+                    offset_x: glyph.x_offset as f32 * scale,
+                    offset_y: (glyph.y_offset as f32 - descriptor.base) * scale,
+                    advance: glyph.x_advance as f32 * scale,
+                },
+                None => GlyphMetrics::default(),
+            }
+        }
+    }
+}
+
+fn kerning(font: &Font, left: char, right: char, size: f32) -> f32 {
+    match font {
+        Font::TrueType(ttf) => ttf.horizontal_kern(left, right, size).unwrap_or(0.0),
+        Font::Bitmap { descriptor, .. } => descriptor
+            .kernings
+            .get(&(left, right))
+            .map_or(0.0, |amount| *amount as f32 * size / descriptor.size),
+    }
+}
+
+fn measure_line(font: &Font, line: &str, size: f32) -> f32 {
+    let mut width = 0.0;
+    let mut previous = None;
+    for ch in line.chars() {
+        if let Some(previous) = previous {
+            width += kerning(font, previous, ch, size);
+        }
+        width += glyph_metrics(font, ch, size).advance;
+        previous = Some(ch);
+    }
+    width
+}
+
+fn read_ttf(path: &Path) -> Result<Font> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Erro ao carregar a fonte {}: {}", path.display(), e))?;
+    parse_ttf(&bytes)
+}
+
+fn parse_ttf(bytes: &[u8]) -> Result<Font> {
+    let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+        .map_err(|e| anyhow::anyhow!("Invalid font: {}", e))?;
+    Ok(Font::TrueType(font))
+}
+
+fn read_bmfont(render: &mut Render, path: &Path) -> Result<Font> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Erro ao carregar a fonte {}: {}", path.display(), e))?;
+    let descriptor = BmFontDescriptor::parse(&text)
+        .map_err(|e| anyhow::anyhow!("Invalid BMFont {}: {}", path.display(), e))?;
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut pages = Vec::with_capacity(descriptor.pages.len());
+    for page in &descriptor.pages {
+        pages.push(render.load_texture(base_dir.join(page))?);
+    }
+    Ok(Font::Bitmap { descriptor, pages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fonte bitmap mínima: 'A' e 'V' com 10 de avanço, espaço com 5 e kerning de -2 entre "AV"
+    const FONT: &str = r#"info face="Test" size=16
+common lineHeight=20 base=16 scaleW=64 scaleH=64 pages=1
+page id=0 file="test.png"
+char id=65 x=0 y=0 width=8 height=10 xoffset=1 yoffset=6 xadvance=10 page=0
+char id=86 x=8 y=0 width=8 height=10 xoffset=1 yoffset=6 xadvance=10 page=0
+char id=32 x=0 y=0 width=0 height=0 xoffset=0 yoffset=0 xadvance=5 page=0
+kerning first=65 second=86 amount=-2
+"#;
+
+    fn renderer() -> (TextRenderer, FontHandle) {
+        let descriptor = BmFontDescriptor::parse(FONT).unwrap();
+        let mut text = TextRenderer::new();
+        let font = text.push(Font::Bitmap { descriptor, pages: vec![TextureHandle::for_test(0)] }, None);
+        (text, font)
+    }
+
+    fn xs(layout: &TextLayout) -> Vec<f32> {
+        layout.glyphs.iter().map(|glyph| glyph.x).collect()
+    }
+
+    #[test]
+    fn glyphs_follow_advance_offsets_and_kerning() {
+        let (text, font) = renderer();
+        let layout = text.layout(font, "AV A", &TextStyle::default());
+
+        // O espaço não gera glifo, só avança a caneta
+        assert_eq!(layout.glyphs.iter().map(|glyph| glyph.ch).collect::<String>(), "AVA");
+        assert_eq!(xs(&layout), [1.0, 9.0, 24.0]);
+        assert!(layout.glyphs.iter().all(|glyph| glyph.y == 6.0 && glyph.width == 8.0 && glyph.height == 10.0));
+        assert_eq!((layout.width, layout.height), (33.0, 20.0));
+        assert_eq!(text.measure(font, "AV A", &TextStyle::default()), [33.0, 20.0]);
+    }
+
+    #[test]
+    fn size_scales_the_bitmap_metrics() {
+        let (text, font) = renderer();
+        let layout = text.layout(font, "AV", &TextStyle { size: 32.0, ..Default::default() });
+        assert_eq!(xs(&layout), [2.0, 18.0]);
+        assert_eq!(layout.glyphs[0].y, 12.0);
+        assert_eq!((layout.width, layout.height), (36.0, 40.0));
+    }
+
+    #[test]
+    fn lines_break_on_newlines_and_max_width() {
+        let (text, font) = renderer();
+        let layout = text.layout(font, "A\nA", &TextStyle { line_spacing: 1.5, ..Default::default() });
+        assert_eq!(layout.height, 60.0);
+        assert_eq!(layout.glyphs[1].y - layout.glyphs[0].y, 30.0);
+
+        // "A A" mede 25, mais que o máximo: cada palavra vai para a sua linha
+        let layout = text.layout(font, "A A A", &TextStyle { max_width: Some(16.0), ..Default::default() });
+        assert_eq!(xs(&layout), [1.0, 1.0, 1.0]);
+        assert_eq!((layout.width, layout.height), (10.0, 60.0));
+
+        // Uma palavra mais larga que o máximo fica sozinha na linha, sem ser cortada
+        let layout = text.layout(font, "AVAV", &TextStyle { max_width: Some(16.0), ..Default::default() });
+        assert_eq!(layout.glyphs.len(), 4);
+        assert_eq!(layout.height, 20.0);
+    }
+
+    #[test]
+    fn alignment_uses_the_block_width() {
+        let (text, font) = renderer();
+        let style = |align| TextStyle { align, max_width: Some(40.0), ..Default::default() };
+        assert_eq!(xs(&text.layout(font, "A", &style(TextAlign::Left))), [1.0]);
+        assert_eq!(xs(&text.layout(font, "A", &style(TextAlign::Center))), [16.0]);
+        assert_eq!(xs(&text.layout(font, "A", &style(TextAlign::Right))), [31.0]);
+
+        // Sem largura máxima, o bloco tem a largura da linha mais longa
+        let center = TextStyle { align: TextAlign::Center, ..Default::default() };
+        assert_eq!(xs(&text.layout(font, "A A\nA", &center)), [1.0, 16.0, 8.5]);
+    }
+
+    #[test]
+    fn unknown_fonts_and_characters_are_skipped() {
+        let (text, font) = renderer();
+        assert_eq!(text.layout(FontHandle(7), "A", &TextStyle::default()), TextLayout::default());
+        let layout = text.layout(font, "AéA", &TextStyle::default());
+        assert_eq!(xs(&layout), [1.0, 11.0]);
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+// Identificador de uma textura carregada no `TextureStore`.
+// A geração evita que um handle antigo aponte para outra textura depois de um `unload`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TextureHandle {
+    index: u32,
+    generation: u32,
+}
+
+#[cfg(test)]
+impl TextureHandle {
+    // Handle sem textura por trás, para testar a lógica de CPU sem GPU
+    pub(crate) fn for_test(index: u32) -> Self {
+        Self { index, generation: 0 }
+    }
+}
+
+// Textura na GPU com a view e o bind group prontos para o shader de sprites
+pub struct Texture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    width: u32,
+    height: u32,
+    path: Option<PathBuf>,  // Caminho de origem, quando carregada de um arquivo
+}
+
+impl Texture {
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+struct Slot {
+    generation: u32,
+    texture: Option<Texture>,
+}
+
+// Registro das texturas do `Render`: entrega handles tipados, evita carregar o mesmo
+// arquivo duas vezes e compartilha um único sampler entre todas as texturas
+pub struct TextureStore {
+    slots: Vec<Slot>,
+    free: Vec<u32>,  // Índices de slots liberados por `unload`
+    by_path: HashMap<PathBuf, TextureHandle>,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    nearest_sampler: wgpu::Sampler,  // Sem interpolação, para render targets de pixel art
+}
+
+impl TextureStore {
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        // Criar layout de binding de textura
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("texture_bind_group_layout"),
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("sprite_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let nearest_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("nearest_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            by_path: HashMap::new(),
+            layout,
+            sampler,
+            nearest_sampler,
+        }
+    }
+
+    pub fn layout(&self) -> &wgpu::BindGroupLayout {
+        &self.layout
+    }
+
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+
+    // Carrega uma imagem do disco; se o arquivo já foi carregado, devolve o mesmo handle
+    pub fn load(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, path: impl AsRef<Path>) -> Result<TextureHandle> {
+        let path = path.as_ref();
+        let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(handle) = self.by_path.get(&key) {
+            return Ok(*handle);
+        }
+
+        let img = image::open(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao carregar a imagem {}: {}", path.display(), e))?
+            .to_rgba8(); // Carrega a imagem
+
+        let mut texture = self.create_texture(device, queue, &img, &path.display().to_string());
+        texture.path = Some(key.clone());
+        let handle = self.insert(texture);
+        self.by_path.insert(key, handle);
+        Ok(handle)
+    }
+
+    // Envia uma imagem já em memória para a GPU (sem deduplicação)
+    pub fn insert_image(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, img: &image::RgbaImage, label: &str) -> TextureHandle {
+        let texture = self.create_texture(device, queue, img, label);
+        self.insert(texture)
+    }
+
+    // Cria uma textura vazia que também pode ser destino de desenho (render target)
+    pub(crate) fn insert_target(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        filter: wgpu::FilterMode,
+    ) -> TextureHandle {
+        let texture = self.create_target_texture(device, width, height, format, filter);
+        self.insert(texture)
+    }
+
+    // Recria a textura de um render target com outro tamanho, mantendo o handle (o conteúdo se perde)
+    pub(crate) fn resize_target(
+        &mut self,
+        device: &wgpu::Device,
+        handle: TextureHandle,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        filter: wgpu::FilterMode,
+    ) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+        let texture = self.create_target_texture(device, width, height, format, filter);
+        self.slots[handle.index as usize].texture = Some(texture);
+        true
+    }
+
+    // Troca o conteúdo da textura mantendo o handle (o tamanho pode mudar).
+    // Com o mesmo tamanho a textura é reaproveitada; senão ela é recriada e os bind groups de
+    // material que a usam precisam ser refeitos (`Render::replace_texture` faz isso).
+    // Retorna `false` se o handle for inválido.
+    pub fn replace(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, handle: TextureHandle, img: &image::RgbaImage) -> bool {
+        let Some(old) = self.get(handle) else {
+            return false;
+        };
+        if old.size() == img.dimensions() && old.texture.usage().contains(wgpu::TextureUsages::COPY_DST) {
+            return self.write_region(queue, handle, 0, 0, img);
+        }
+        let path = old.path.clone();
+        let label = path.as_ref().map_or_else(|| String::from("texture"), |path| path.display().to_string());
+        let mut texture = self.create_texture(device, queue, img, &label);
+        texture.path = path;
+        // A textura antiga só é liberada quando a GPU terminar de usá-la (sem `destroy`)
+        self.slots[handle.index as usize].texture = Some(texture);
+        true
+    }
+
+    // Relê do disco uma textura carregada por `load`; `None` se nenhuma veio desse arquivo
+    pub fn reload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, path: impl AsRef<Path>) -> Result<Option<TextureHandle>> {
+        let Some(handle) = self.handle_for_path(&path) else {
+            return Ok(None);
+        };
+        let path = path.as_ref();
+        let img = image::open(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao carregar a imagem {}: {}", path.display(), e))?
+            .to_rgba8();
+        self.replace(device, queue, handle, &img);
+        Ok(Some(handle))
+    }
+
+    // Atualiza uma parte da textura com `img`, a partir de (x, y).
+    // Retorna `false` se o handle for inválido ou a região sair da textura.
+    pub fn write_region(&self, queue: &wgpu::Queue, handle: TextureHandle, x: u32, y: u32, img: &image::RgbaImage) -> bool {
+        let Some(texture) = self.get(handle) else {
+            return false;
+        };
+        let (width, height) = img.dimensions();
+        // `checked_add`: um `x` perto de `u32::MAX` não pode dar a volta e passar pela checagem
+        let outside_x = x.checked_add(width).is_none_or(|right| right > texture.width);
+        let outside_y = y.checked_add(height).is_none_or(|bottom| bottom > texture.height);
+        if width == 0 || height == 0 || outside_x || outside_y {
+            return false;
+        }
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            img,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * width),
+                rows_per_image: NonZeroU32::new(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        true
+    }
+
+    pub fn get(&self, handle: TextureHandle) -> Option<&Texture> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.texture.as_ref())
+    }
+
+    pub fn contains(&self, handle: TextureHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn bind_group(&self, handle: TextureHandle) -> Option<&wgpu::BindGroup> {
+        self.get(handle).map(Texture::bind_group)
+    }
+
+    pub fn dimensions(&self, handle: TextureHandle) -> Option<(u32, u32)> {
+        self.get(handle).map(Texture::size)
+    }
+
+    pub fn handle_for_path(&self, path: impl AsRef<Path>) -> Option<TextureHandle> {
+        let path = path.as_ref();
+        let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.by_path.get(&key).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.texture.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Libera a textura da GPU. Handles antigos passam a ser inválidos.
+    // Retorna `false` se o handle já não era válido.
+    pub fn unload(&mut self, handle: TextureHandle) -> bool {
+        let Some(slot) = self.slots.get_mut(handle.index as usize) else {
+            return false;
+        };
+        if slot.generation != handle.generation {
+            return false;
+        }
+        let Some(texture) = slot.texture.take() else {
+            return false;
+        };
+
+        if let Some(path) = &texture.path {
+            self.by_path.remove(path);
+        }
+        texture.texture.destroy();
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        true
+    }
+
+    fn insert(&mut self, texture: Texture) -> TextureHandle {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.texture = Some(texture);
+                TextureHandle { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, texture: Some(texture) });
+                TextureHandle {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    fn create_texture(&self, device: &wgpu::Device, queue: &wgpu::Queue, img: &image::RgbaImage, label: &str) -> Texture {
+        let dimensions = img.dimensions();
+
+        let texture_size = wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        };
+
+        // Cria a textura na GPU antes de enviar os dados
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: texture_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        // Envia os dados da imagem para a GPU (write_texture não exige alinhamento de linha)
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            img,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * dimensions.0),
+                rows_per_image: NonZeroU32::new(dimensions.1),
+            },
+            texture_size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.create_bind_group(device, &view, wgpu::FilterMode::Linear);
+
+        Texture {
+            texture,
+            view,
+            bind_group,
+            width: dimensions.0,
+            height: dimensions.1,
+            path: None,
+        }
+    }
+
+    fn create_target_texture(
+        &self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        filter: wgpu::FilterMode,
+    ) -> Texture {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("render_target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            // Desenhada num passe, amostrada como sprite e copiada em `read_target_pixels`
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.create_bind_group(device, &view, filter);
+
+        Texture {
+            texture,
+            view,
+            bind_group,
+            width,
+            height,
+            path: None,
+        }
+    }
+
+    pub(crate) fn create_bind_group(&self, device: &wgpu::Device, view: &wgpu::TextureView, filter: wgpu::FilterMode) -> wgpu::BindGroup {
+        let sampler = match filter {
+            wgpu::FilterMode::Linear => &self.sampler,
+            wgpu::FilterMode::Nearest => &self.nearest_sampler,
+        };
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+            label: Some("texture_bind_group"),
+        })
+    }
+}
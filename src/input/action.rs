@@ -0,0 +1,687 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Result;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::is_ron;
+
+use super::{GamepadAxis, GamepadButton, GamepadId, Input, Key, MouseButton};
+
+// Contexto das ações criadas sem `Action::context`; começa ativo
+pub const DEFAULT_CONTEXT: &str = "default";
+
+// Valor a partir do qual uma entrada analógica conta como "pressionada"
+const PRESS_THRESHOLD: f32 = 0.5;
+
+const DEFAULT_DEAD_ZONE: f32 = 0.15;
+
+// Uma entrada física. Meio-eixos permitem usar um gatilho ou um lado do analógico como botão.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    GamepadAxisPositive(GamepadAxis),
+    GamepadAxisNegative(GamepadAxis),
+}
+
+impl Binding {
+    // Valor atual (0..1), sem zona morta
+    fn value(self, input: &Input, gamepad: Option<GamepadId>) -> f32 {
+        let pressed = |yes: bool| if yes { 1.0 } else { 0.0 };
+        match self {
+            Binding::Key(key) => pressed(input.is_pressed(key)),
+            Binding::Mouse(button) => pressed(input.is_mouse_pressed(button)),
+            Binding::GamepadButton(button) => {
+                pressed(gamepads(input, gamepad).any(|id| input.gamepad_pressed(id, button)))
+            }
+            Binding::GamepadAxisPositive(axis) => gamepad_axis(input, gamepad, axis).max(0.0),
+            Binding::GamepadAxisNegative(axis) => (-gamepad_axis(input, gamepad, axis)).max(0.0),
+        }
+    }
+
+    fn is_pressed(self, input: &Input, gamepad: Option<GamepadId>) -> bool {
+        self.value(input, gamepad) >= PRESS_THRESHOLD
+    }
+}
+
+impl From<Key> for Binding {
+    fn from(key: Key) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::Mouse(button)
+    }
+}
+
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Self {
+        Binding::GamepadButton(button)
+    }
+}
+
+// Como uma ação lê as entradas. Ações de botão usam `value().x` como 0 ou 1;
+// ações de eixo usam x (-1..1); ações 2D usam o vetor todo.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputBinding {
+    Button(Binding),
+    Chord(Vec<Binding>),  // Todas ao mesmo tempo (ex.: Ctrl + S)
+    Axis(GamepadAxis),    // Eixo analógico, com zona morta
+    Composite { negative: Binding, positive: Binding },  // Ex.: A/D viram -1..1
+    Stick { x: GamepadAxis, y: GamepadAxis },  // Analógico 2D, zona morta radial
+    Composite2D { up: Binding, down: Binding, left: Binding, right: Binding },  // Ex.: WASD
+}
+
+impl InputBinding {
+    pub fn chord(bindings: impl IntoIterator<Item = impl Into<Binding>>) -> Self {
+        InputBinding::Chord(bindings.into_iter().map(Into::into).collect())
+    }
+
+    pub fn wasd() -> Self {
+        InputBinding::Composite2D {
+            up: Binding::Key(Key::W),
+            down: Binding::Key(Key::S),
+            left: Binding::Key(Key::A),
+            right: Binding::Key(Key::D),
+        }
+    }
+
+    pub fn arrows() -> Self {
+        InputBinding::Composite2D {
+            up: Binding::Key(Key::Up),
+            down: Binding::Key(Key::Down),
+            left: Binding::Key(Key::Left),
+            right: Binding::Key(Key::Right),
+        }
+    }
+
+    pub fn left_stick() -> Self {
+        InputBinding::Stick {
+            x: GamepadAxis::LeftStickX,
+            y: GamepadAxis::LeftStickY,
+        }
+    }
+
+    pub fn right_stick() -> Self {
+        InputBinding::Stick {
+            x: GamepadAxis::RightStickX,
+            y: GamepadAxis::RightStickY,
+        }
+    }
+
+    fn value(&self, input: &Input, gamepad: Option<GamepadId>, dead_zone: f32, suppressed: &HashSet<Binding>) -> Vec2 {
+        let button = |binding: &Binding| {
+            if suppressed.contains(binding) {
+                0.0
+            } else {
+                binding.value(input, gamepad)
+            }
+        };
+        match self {
+            InputBinding::Button(binding) => Vec2::new(button(binding), 0.0),
+            InputBinding::Chord(bindings) => {
+                let held = !bindings.is_empty() && bindings.iter().all(|binding| binding.is_pressed(input, gamepad));
+                Vec2::new(if held { 1.0 } else { 0.0 }, 0.0)
+            }
+            InputBinding::Axis(axis) => {
+                Vec2::new(apply_dead_zone(gamepad_axis(input, gamepad, *axis), dead_zone), 0.0)
+            }
+            InputBinding::Composite { negative, positive } => Vec2::new(button(positive) - button(negative), 0.0),
+            InputBinding::Stick { x, y } => {
+                let stick = Vec2::new(gamepad_axis(input, gamepad, *x), gamepad_axis(input, gamepad, *y));
+                let length = stick.length();
+                if length <= dead_zone {
+                    Vec2::ZERO
+                } else {
+                    stick / length * apply_dead_zone(length, dead_zone).min(1.0)
+                }
+            }
+            InputBinding::Composite2D { up, down, left, right } => {
+                Vec2::new(button(right) - button(left), button(up) - button(down)).clamp_length_max(1.0)
+            }
+        }
+    }
+
+    // Combinações que disparam esta ligação, para a detecção de conflitos
+    fn triggers(&self) -> Vec<Vec<Binding>> {
+        match self {
+            InputBinding::Button(binding) => vec![vec![*binding]],
+            InputBinding::Chord(bindings) => {
+                // Ordem canônica: Ctrl + S e S + Ctrl são o mesmo acorde
+                let mut chord = bindings.clone();
+                chord.sort_by_key(|binding| format!("{:?}", binding));
+                chord.dedup();
+                vec![chord]
+            }
+            InputBinding::Axis(axis) => vec![
+                vec![Binding::GamepadAxisPositive(*axis)],
+                vec![Binding::GamepadAxisNegative(*axis)],
+            ],
+            InputBinding::Composite { negative, positive } => vec![vec![*negative], vec![*positive]],
+            InputBinding::Stick { x, y } => [*x, *y]
+                .into_iter()
+                .flat_map(|axis| [Binding::GamepadAxisPositive(axis), Binding::GamepadAxisNegative(axis)])
+                .map(|binding| vec![binding])
+                .collect(),
+            InputBinding::Composite2D { up, down, left, right } => {
+                vec![vec![*up], vec![*down], vec![*left], vec![*right]]
+            }
+        }
+    }
+}
+
+impl From<Binding> for InputBinding {
+    fn from(binding: Binding) -> Self {
+        InputBinding::Button(binding)
+    }
+}
+
+impl From<Key> for InputBinding {
+    fn from(key: Key) -> Self {
+        InputBinding::Button(Binding::Key(key))
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(button: MouseButton) -> Self {
+        InputBinding::Button(Binding::Mouse(button))
+    }
+}
+
+impl From<GamepadButton> for InputBinding {
+    fn from(button: GamepadButton) -> Self {
+        InputBinding::Button(Binding::GamepadButton(button))
+    }
+}
+
+// Uma ação lógica ("Pular", "Mover") e as entradas ligadas a ela
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Action {
+    #[serde(default = "default_context")]
+    pub context: String,
+    pub bindings: Vec<InputBinding>,
+    #[serde(default = "default_dead_zone")]
+    pub dead_zone: f32,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self {
+            context: default_context(),
+            bindings: Vec::new(),
+            dead_zone: DEFAULT_DEAD_ZONE,
+        }
+    }
+}
+
+impl Action {
+    pub fn bind(&mut self, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        if !self.bindings.contains(&binding) {
+            self.bindings.push(binding);
+        }
+        self
+    }
+
+    pub fn unbind(&mut self, binding: &InputBinding) -> &mut Self {
+        self.bindings.retain(|existing| existing != binding);
+        self
+    }
+
+    pub fn context(&mut self, context: impl Into<String>) -> &mut Self {
+        self.context = context.into();
+        self
+    }
+
+    pub fn dead_zone(&mut self, dead_zone: f32) -> &mut Self {
+        self.dead_zone = dead_zone.clamp(0.0, 0.99);
+        self
+    }
+}
+
+fn default_context() -> String {
+    DEFAULT_CONTEXT.to_string()
+}
+
+fn default_dead_zone() -> f32 {
+    DEFAULT_DEAD_ZONE
+}
+
+// Estado de uma ação no frame atual
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ActionState {
+    pub value: Vec2,
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+}
+
+// Duas ações do mesmo contexto disparadas pela mesma entrada (ou combinação)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub context: String,
+    pub trigger: Vec<Binding>,
+    pub actions: [String; 2],
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is bound to both '{}' and '{}' in context '{}'",
+            self.trigger, self.actions[0], self.actions[1], self.context
+        )
+    }
+}
+
+// Camada de ações sobre o `Input`: o jogo pergunta por "Pular" em vez de por Espaço ou A.
+// As ligações podem ser salvas e carregadas (RON ou JSON) para o jogador trocar os controles.
+//
+//     actions.action("Jump").bind(Key::Space).bind(GamepadButton::South);
+//     actions.action("Move").bind(InputBinding::wasd()).bind(InputBinding::left_stick());
+//     actions.action("Save").bind(InputBinding::chord([Key::LControl, Key::S]));
+//     ...
+//     if ctx.actions.just_pressed("Jump") { ... }
+//     let direction = ctx.actions.axis_2d("Move");
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Action>,
+    #[serde(skip, default = "default_active_contexts")]
+    active_contexts: BTreeSet<String>,
+    #[serde(skip)]
+    gamepad: Option<GamepadId>,  // `None` = qualquer gamepad conectado
+    #[serde(skip)]
+    states: HashMap<String, ActionState>,
+}
+
+fn default_active_contexts() -> BTreeSet<String> {
+    BTreeSet::from([default_context()])
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self {
+            actions: BTreeMap::new(),
+            active_contexts: default_active_contexts(),
+            gamepad: None,
+            states: HashMap::new(),
+        }
+    }
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Devolve a ação, criando-a (sem ligações, no contexto padrão) se não existir
+    pub fn action(&mut self, name: impl Into<String>) -> &mut Action {
+        self.actions.entry(name.into()).or_default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Action> {
+        self.actions.get(name)
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = (&str, &Action)> {
+        self.actions.iter().map(|(name, action)| (name.as_str(), action))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Action> {
+        self.states.remove(name);
+        self.actions.remove(name)
+    }
+
+    // Troca uma ligação da ação por outra (tela de controles)
+    pub fn rebind(&mut self, name: &str, old: &InputBinding, new: impl Into<InputBinding>) -> bool {
+        let Some(action) = self.actions.get_mut(name) else {
+            return false;
+        };
+        let Some(slot) = action.bindings.iter_mut().find(|binding| *binding == old) else {
+            return false;
+        };
+        *slot = new.into();
+        true
+    }
+
+    pub fn activate_context(&mut self, context: impl Into<String>) {
+        self.active_contexts.insert(context.into());
+    }
+
+    pub fn deactivate_context(&mut self, context: &str) {
+        self.active_contexts.remove(context);
+    }
+
+    // Deixa só `context` ativo (ex.: abrir o menu desativa o gameplay)
+    pub fn set_context(&mut self, context: impl Into<String>) {
+        self.active_contexts.clear();
+        self.active_contexts.insert(context.into());
+    }
+
+    pub fn is_context_active(&self, context: &str) -> bool {
+        self.active_contexts.contains(context)
+    }
+
+    // Limita os gamepads lidos a um só (multijogador local); `None` aceita qualquer um
+    pub fn set_gamepad(&mut self, gamepad: Option<GamepadId>) {
+        self.gamepad = gamepad;
+    }
+
+    // Recalcula o estado das ações a partir do `Input` do frame (o `AppRunner` faz isso antes do `update`)
+    pub fn update(&mut self, input: &Input) {
+        // Entradas que fazem parte de um acorde completo não disparam ações de botão simples
+        // do mesmo contexto (Ctrl + S não deve também acionar a ação ligada só ao S)
+        let mut suppressed: HashMap<&str, HashSet<Binding>> = HashMap::new();
+        for action in self.actions.values() {
+            for binding in &action.bindings {
+                if let InputBinding::Chord(chord) = binding {
+                    if chord.iter().all(|binding| binding.is_pressed(input, self.gamepad)) {
+                        suppressed.entry(action.context.as_str()).or_default().extend(chord.iter().copied());
+                    }
+                }
+            }
+        }
+
+        let empty = HashSet::new();
+        for (name, action) in &self.actions {
+            let active = self.active_contexts.contains(&action.context);
+            let suppressed = suppressed.get(action.context.as_str()).unwrap_or(&empty);
+
+            // Entre várias ligações vale a de maior intensidade (teclado e analógico não se somam)
+            let value = if active {
+                action
+                    .bindings
+                    .iter()
+                    .map(|binding| binding.value(input, self.gamepad, action.dead_zone, suppressed))
+                    .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+                    .unwrap_or(Vec2::ZERO)
+            } else {
+                Vec2::ZERO
+            };
+
+            let state = self.states.entry(name.clone()).or_default();
+            let pressed = value.length() >= PRESS_THRESHOLD;
+            *state = ActionState {
+                value,
+                pressed,
+                just_pressed: pressed && !state.pressed,
+                just_released: !pressed && state.pressed,
+            };
+        }
+    }
+
+    pub fn state(&self, name: &str) -> ActionState {
+        self.states.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn pressed(&self, name: &str) -> bool {
+        self.state(name).pressed
+    }
+
+    pub fn just_pressed(&self, name: &str) -> bool {
+        self.state(name).just_pressed
+    }
+
+    pub fn just_released(&self, name: &str) -> bool {
+        self.state(name).just_released
+    }
+
+    // Valor de uma ação de eixo (-1..1)
+    pub fn axis(&self, name: &str) -> f32 {
+        self.state(name).value.x
+    }
+
+    // Valor de uma ação 2D (comprimento até 1, y para cima)
+    pub fn axis_2d(&self, name: &str) -> Vec2 {
+        self.state(name).value
+    }
+
+    // Primeira entrada pressionada neste frame, para telas de "aperte uma tecla"
+    pub fn capture(input: &Input) -> Option<Binding> {
+        if let Some(key) = input.keys().iter_just_pressed().next() {
+            return Some(Binding::Key(key));
+        }
+        if let Some(button) = input.mouse_buttons().iter_just_pressed().next() {
+            return Some(Binding::Mouse(button));
+        }
+        input
+            .gamepads()
+            .filter_map(|id| input.gamepad(id))
+            .find_map(|gamepad| gamepad.buttons().iter_just_pressed().next())
+            .map(Binding::GamepadButton)
+    }
+
+    // Todas as entradas usadas por mais de uma ação no mesmo contexto
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut seen: HashMap<(&str, Vec<Binding>), &str> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for (name, action) in &self.actions {
+            for trigger in action.bindings.iter().flat_map(InputBinding::triggers) {
+                match seen.get(&(action.context.as_str(), trigger.clone())) {
+                    Some(other) if *other != name.as_str() => conflicts.push(Conflict {
+                        context: action.context.clone(),
+                        trigger,
+                        actions: [other.to_string(), name.clone()],
+                    }),
+                    Some(_) => {}
+                    None => {
+                        seen.insert((action.context.as_str(), trigger), name.as_str());
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    // Verifica se ligar `binding` à ação `name` causaria conflito, antes de aplicar
+    pub fn find_conflict(&self, name: &str, binding: &InputBinding) -> Option<Conflict> {
+        let context = self.actions.get(name).map_or(DEFAULT_CONTEXT, |action| action.context.as_str());
+        let triggers = binding.triggers();
+        self.actions
+            .iter()
+            .filter(|(other, action)| other.as_str() != name && action.context == context)
+            .find_map(|(other, action)| {
+                let trigger = action
+                    .bindings
+                    .iter()
+                    .flat_map(InputBinding::triggers)
+                    .find(|trigger| triggers.contains(trigger))?;
+                Some(Conflict {
+                    context: context.to_string(),
+                    trigger,
+                    actions: [other.clone(), name.to_string()],
+                })
+            })
+    }
+
+    // Carrega as ligações de um arquivo .ron ou .json. Os contextos ativos não são salvos.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao ler os controles {}: {}", path.display(), e))?;
+        let mut map: Self = if is_ron(path) {
+            ron::from_str(&text).map_err(|e| anyhow::anyhow!("Controles RON inválidos {}: {}", path.display(), e))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| anyhow::anyhow!("Controles JSON inválidos {}: {}", path.display(), e))?
+        };
+        // O arquivo não passa pelo `Action::dead_zone`: uma zona morta negativa faria o
+        // analógico parado contar como movimento
+        for action in map.actions.values_mut() {
+            let dead_zone = action.dead_zone;
+            action.dead_zone(dead_zone);
+        }
+        Ok(map)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = if is_ron(path) {
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, text).map_err(|e| anyhow::anyhow!("Erro ao salvar os controles {}: {}", path.display(), e))
+    }
+
+    // Troca as ligações pelas de `other` (ex.: recém carregadas), mantendo contextos e estado
+    pub fn apply_bindings(&mut self, other: ActionMap) {
+        self.actions = other.actions;
+        self.states.retain(|name, _| self.actions.contains_key(name));
+    }
+}
+
+fn gamepads(input: &Input, gamepad: Option<GamepadId>) -> impl Iterator<Item = GamepadId> + '_ {
+    input.gamepads().filter(move |id| gamepad.is_none_or(|only| only == *id))
+}
+
+// Eixo com maior intensidade entre os gamepads aceitos
+fn gamepad_axis(input: &Input, gamepad: Option<GamepadId>, axis: GamepadAxis) -> f32 {
+    gamepads(input, gamepad)
+        .map(|id| input.gamepad_axis(id, axis))
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0)
+}
+
+// Zera valores dentro da zona morta e reescala o resto para continuar indo de 0 a 1
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value.abs() <= dead_zone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputEvent;
+
+    fn press(input: &mut Input, keys: &[Key]) {
+        for &key in keys {
+            input.inject(InputEvent::KeyPressed(key));
+        }
+    }
+
+    #[test]
+    fn chords_suppress_single_bindings_of_the_same_context() {
+        let mut actions = ActionMap::new();
+        actions.action("Save").bind(InputBinding::chord([Key::LControl, Key::S]));
+        actions.action("Back").bind(Key::S);
+        actions.action("Menu").context("menu").bind(Key::S);
+        actions.activate_context("menu");
+
+        let mut input = Input::new();
+        press(&mut input, &[Key::S]);
+        actions.update(&input);
+        assert!(actions.just_pressed("Back") && !actions.pressed("Save"));
+
+        input.end_frame();
+        press(&mut input, &[Key::LControl]);
+        actions.update(&input);
+        assert!(actions.just_pressed("Save"));
+        assert!(actions.just_released("Back"));
+        // Outro contexto não é afetado pelo acorde
+        assert!(actions.pressed("Menu"));
+    }
+
+    #[test]
+    fn inactive_contexts_read_as_released() {
+        let mut actions = ActionMap::new();
+        actions.action("Jump").bind(Key::Space);
+        actions.action("Confirm").context("menu").bind(Key::Space);
+
+        let mut input = Input::new();
+        press(&mut input, &[Key::Space]);
+        actions.update(&input);
+        assert!(actions.pressed("Jump") && !actions.pressed("Confirm"));
+
+        actions.set_context("menu");
+        input.end_frame();
+        actions.update(&input);
+        assert!(actions.just_released("Jump"));
+        assert!(actions.just_pressed("Confirm"));
+        assert!(!actions.is_context_active(DEFAULT_CONTEXT));
+    }
+
+    #[test]
+    fn composites_and_sticks() {
+        let mut actions = ActionMap::new();
+        actions.action("Move").bind(InputBinding::wasd()).bind(InputBinding::left_stick());
+        actions.action("Steer").bind(InputBinding::Axis(GamepadAxis::LeftStickX));
+
+        let pad = GamepadId(0);
+        let mut input = Input::new();
+        press(&mut input, &[Key::D, Key::W]);
+        input.inject(InputEvent::GamepadAxisChanged(pad, GamepadAxis::LeftStickX, 0.1));
+        actions.update(&input);
+        let direction = actions.axis_2d("Move");
+        assert!((direction.length() - 1.0).abs() < 1e-5 && direction.x > 0.0 && direction.y > 0.0);
+        assert_eq!(actions.axis("Steer"), 0.0);  // Dentro da zona morta
+
+        input.inject(InputEvent::GamepadAxisChanged(pad, GamepadAxis::LeftStickX, -1.0));
+        actions.update(&input);
+        assert_eq!(actions.axis("Steer"), -1.0);
+    }
+
+    #[test]
+    fn conflicts_are_found_per_context() {
+        let mut actions = ActionMap::new();
+        actions.action("Jump").bind(Key::Space);
+        actions.action("Fire").bind(Key::Space).bind(InputBinding::chord([Key::S, Key::LControl]));
+        actions.action("Save").bind(InputBinding::chord([Key::LControl, Key::S]));
+        actions.action("Confirm").context("menu").bind(Key::Space);
+
+        let conflicts = actions.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.iter().all(|conflict| conflict.context == DEFAULT_CONTEXT));
+        assert!(conflicts.iter().any(|conflict| conflict.trigger == [Binding::Key(Key::Space)]));
+
+        assert!(actions.find_conflict("Jump", &InputBinding::wasd()).is_none());
+        assert!(actions.find_conflict("Confirm", &Key::Space.into()).is_none());
+        let conflict = actions.find_conflict("Jump", &InputBinding::chord([Key::LControl, Key::S])).unwrap();
+        assert_eq!(conflict.actions[1], "Jump");
+    }
+
+    #[test]
+    fn bindings_round_trip_through_ron_and_json() {
+        let mut actions = ActionMap::new();
+        actions.action("Jump").bind(Key::Space).bind(GamepadButton::South);
+        actions.action("Move").bind(InputBinding::wasd()).bind(InputBinding::left_stick()).dead_zone(0.3);
+        actions.action("Save").context("editor").bind(InputBinding::chord([Key::LControl, Key::S]));
+        actions.action("Throttle").bind(Binding::GamepadAxisPositive(GamepadAxis::RightTrigger));
+
+        let dir = std::env::temp_dir();
+        for extension in ["ron", "json"] {
+            let path = dir.join(format!("base-actions-test-{}.{}", std::process::id(), extension));
+            actions.save(&path).unwrap();
+            let loaded = ActionMap::load(&path);
+            let _ = std::fs::remove_file(&path);
+            let loaded = loaded.unwrap();
+            assert!(loaded.actions().eq(actions.actions()), "{}", extension);
+            assert!(loaded.is_context_active(DEFAULT_CONTEXT) && !loaded.is_context_active("editor"));
+        }
+    }
+
+    #[test]
+    fn loaded_dead_zones_are_clamped() {
+        let path = std::env::temp_dir().join(format!("base-dead-zone-test-{}.json", std::process::id()));
+        let text = r#"{ "actions": { "Steer": { "bindings": [ { "Axis": "LeftStickX" } ], "dead_zone": -0.5 } } }"#;
+        std::fs::write(&path, text).unwrap();
+        let loaded = ActionMap::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let mut actions = loaded.unwrap();
+        assert_eq!(actions.get("Steer").unwrap().dead_zone, 0.0);
+
+        // Analógico parado continua parado
+        let mut input = Input::new();
+        input.inject(InputEvent::GamepadConnected(GamepadId(0)));
+        actions.update(&input);
+        assert_eq!(actions.axis("Steer"), 0.0);
+        assert!(!actions.pressed("Steer"));
+    }
+}
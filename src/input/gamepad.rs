@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ButtonInput, InputEvent};
+
+// Identificador de um gamepad conectado, atribuído pelo backend
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GamepadId(pub usize);
+
+// Botões no layout de um controle de Xbox/PlayStation (nomes por posição, como no gilrs)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,  // A / Cruz
+    East,   // B / Círculo
+    North,  // Y / Triângulo
+    West,   // X / Quadrado
+    LeftBumper,
+    LeftTrigger,
+    RightBumper,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,   // Positivo = para cima
+    RightStickX,
+    RightStickY,
+    LeftTrigger,  // 0..1
+    RightTrigger,
+}
+
+// Estado de um gamepad conectado
+#[derive(Clone, Debug, Default)]
+pub struct GamepadState {
+    pub name: String,
+    pub(crate) buttons: ButtonInput<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+impl GamepadState {
+    pub fn buttons(&self) -> &ButtonInput<GamepadButton> {
+        &self.buttons
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    pub(crate) fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes.insert(axis, value.clamp(-1.0, 1.0));
+    }
+}
+
+// Fonte de eventos de gamepad, consultada uma vez por frame antes do `update`.
+// O padrão (feature `gamepad`) usa o gilrs; outros backends ou fakes de teste podem ser usados no lugar.
+pub trait GamepadBackend {
+    fn poll(&mut self, events: &mut Vec<InputEvent>);
+
+    // Nome do dispositivo, se o backend souber
+    fn name(&self, _id: GamepadId) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(feature = "gamepad")]
+pub struct GilrsBackend {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl GilrsBackend {
+    pub fn new() -> anyhow::Result<Self> {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            // Plataforma sem suporte: segue com um contexto vazio, sem gamepads
+            Err(gilrs::Error::NotImplemented(gilrs)) => gilrs,
+            Err(e) => return Err(anyhow::anyhow!("Failed to initialize gilrs: {}", e)),
+        };
+        Ok(Self { gilrs })
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) {
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let id = GamepadId(id.into());
+            match event {
+                gilrs::EventType::Connected => events.push(InputEvent::GamepadConnected(id)),
+                gilrs::EventType::Disconnected => events.push(InputEvent::GamepadDisconnected(id)),
+                gilrs::EventType::ButtonPressed(button, _) => {
+                    if let Some(button) = convert_button(button) {
+                        events.push(InputEvent::GamepadPressed(id, button));
+                    }
+                }
+                gilrs::EventType::ButtonReleased(button, _) => {
+                    if let Some(button) = convert_button(button) {
+                        events.push(InputEvent::GamepadReleased(id, button));
+                    }
+                }
+                // Gatilhos analógicos chegam como botões com valor
+                gilrs::EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => {
+                    events.push(InputEvent::GamepadAxisChanged(id, GamepadAxis::LeftTrigger, value));
+                }
+                gilrs::EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => {
+                    events.push(InputEvent::GamepadAxisChanged(id, GamepadAxis::RightTrigger, value));
+                }
+                gilrs::EventType::AxisChanged(axis, value, _) => {
+                    if let Some(axis) = convert_axis(axis) {
+                        events.push(InputEvent::GamepadAxisChanged(id, axis, value));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn name(&self, id: GamepadId) -> Option<String> {
+        self.gilrs
+            .gamepads()
+            .find(|(gilrs_id, _)| usize::from(*gilrs_id) == id.0)
+            .map(|(_, gamepad)| gamepad.name().to_string())
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn convert_button(button: gilrs::Button) -> Option<GamepadButton> {
+    Some(match button {
+        gilrs::Button::South => GamepadButton::South,
+        gilrs::Button::East => GamepadButton::East,
+        gilrs::Button::North => GamepadButton::North,
+        gilrs::Button::West => GamepadButton::West,
+        gilrs::Button::LeftTrigger => GamepadButton::LeftBumper,
+        gilrs::Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        gilrs::Button::RightTrigger => GamepadButton::RightBumper,
+        gilrs::Button::RightTrigger2 => GamepadButton::RightTrigger,
+        gilrs::Button::Select => GamepadButton::Select,
+        gilrs::Button::Start => GamepadButton::Start,
+        gilrs::Button::Mode => GamepadButton::Mode,
+        gilrs::Button::LeftThumb => GamepadButton::LeftThumb,
+        gilrs::Button::RightThumb => GamepadButton::RightThumb,
+        gilrs::Button::DPadUp => GamepadButton::DPadUp,
+        gilrs::Button::DPadDown => GamepadButton::DPadDown,
+        gilrs::Button::DPadLeft => GamepadButton::DPadLeft,
+        gilrs::Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "gamepad")]
+fn convert_axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+    Some(match axis {
+        gilrs::Axis::LeftStickX => GamepadAxis::LeftStickX,
+        gilrs::Axis::LeftStickY => GamepadAxis::LeftStickY,
+        gilrs::Axis::RightStickX => GamepadAxis::RightStickX,
+        gilrs::Axis::RightStickY => GamepadAxis::RightStickY,
+        _ => return None,
+    })
+}
@@ -0,0 +1,419 @@
+pub mod action;
+pub mod gamepad;
+pub mod replay;
+
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use winit::event::{DeviceEvent, ElementState, KeyboardInput, MouseScrollDelta, WindowEvent};
+
+pub use action::{ActionMap, Binding, InputBinding};
+pub use gamepad::{GamepadAxis, GamepadBackend, GamepadButton, GamepadId, GamepadState};
+pub use replay::{InputRecorder, InputReplayer, Recording};
+#[cfg(feature = "gamepad")]
+pub use gamepad::GilrsBackend;
+pub use winit::event::{MouseButton, VirtualKeyCode as Key};
+
+// Quantos pixels de rolagem (touchpads) equivalem a uma linha da roda do mouse
+const PIXELS_PER_LINE: f32 = 16.0;
+
+// Estado de um conjunto de botões (teclas, botões do mouse, botões de gamepad).
+// `just_pressed`/`just_released` valem só no frame em que a mudança aconteceu.
+#[derive(Clone, Debug)]
+pub struct ButtonInput<T: Copy + Eq + Hash> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for ButtonInput<T> {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> ButtonInput<T> {
+    // Repetições do sistema (tecla segurada) não contam como um novo `just_pressed`
+    pub fn press(&mut self, button: T) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    pub fn release(&mut self, button: T) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    pub fn is_pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    pub fn just_released(&self, button: T) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    pub fn any_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.is_pressed(button))
+    }
+
+    pub fn iter_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.pressed.iter().copied()
+    }
+
+    pub fn iter_just_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.just_pressed.iter().copied()
+    }
+
+    // Esquece as mudanças do frame; os botões continuam pressionados
+    pub fn clear_frame(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+// Um evento de entrada já independente do winit. É o que `Input` consome:
+// os eventos da janela são convertidos para isto, e testes podem injetá-los com `Input::inject`.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    KeyPressed(Key),
+    KeyReleased(Key),
+    MousePressed(MouseButton),
+    MouseReleased(MouseButton),
+    CursorMoved([f32; 2]),    // Posição na janela, em pixels físicos (origem no canto superior esquerdo)
+    MouseMotion([f32; 2]),    // Movimento bruto do dispositivo (funciona com o cursor preso)
+    MouseWheel([f32; 2]),     // Em linhas; positivo = para cima / direita
+    CursorLeft,               // O próximo `CursorMoved` não gera delta
+    Text(char),
+    FocusLost,                // Solta tudo o que estava pressionado
+    GamepadConnected(GamepadId),
+    GamepadDisconnected(GamepadId),
+    GamepadPressed(GamepadId, GamepadButton),
+    GamepadReleased(GamepadId, GamepadButton),
+    GamepadAxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+// Estado de teclado, mouse e gamepads consultado pela lógica do jogo a cada frame.
+// O `AppRunner` alimenta os eventos e chama `end_frame` depois do `update`.
+#[derive(Clone, Debug, Default)]
+pub struct Input {
+    keys: ButtonInput<Key>,
+    mouse_buttons: ButtonInput<MouseButton>,
+    mouse_position: Vec2,
+    mouse_delta: Vec2,
+    mouse_motion: Vec2,
+    mouse_wheel: Vec2,
+    cursor_inside: bool,
+    text: String,
+    gamepads: BTreeMap<GamepadId, GamepadState>,
+    events: Vec<InputEvent>,  // Eventos aplicados neste frame, em ordem (gravação de replays)
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Converte e aplica um evento da janela; retorna `true` se o evento era de entrada
+    pub fn handle_window_event(&mut self, event: &WindowEvent<'_>) -> bool {
+        match event {
+            WindowEvent::KeyboardInput {
+                input: KeyboardInput { virtual_keycode: Some(key), state, .. },
+                ..
+            } => self.inject(match state {
+                ElementState::Pressed => InputEvent::KeyPressed(*key),
+                ElementState::Released => InputEvent::KeyReleased(*key),
+            }),
+            WindowEvent::MouseInput { button, state, .. } => self.inject(match state {
+                ElementState::Pressed => InputEvent::MousePressed(*button),
+                ElementState::Released => InputEvent::MouseReleased(*button),
+            }),
+            WindowEvent::CursorMoved { position, .. } => {
+                self.inject(InputEvent::CursorMoved([position.x as f32, position.y as f32]))
+            }
+            WindowEvent::MouseWheel { delta, .. } => self.inject(InputEvent::MouseWheel(match delta {
+                MouseScrollDelta::LineDelta(x, y) => [*x, *y],
+                MouseScrollDelta::PixelDelta(offset) => {
+                    [offset.x as f32 / PIXELS_PER_LINE, offset.y as f32 / PIXELS_PER_LINE]
+                }
+            })),
+            WindowEvent::ReceivedCharacter(c) => self.inject(InputEvent::Text(*c)),
+            WindowEvent::Focused(false) => self.inject(InputEvent::FocusLost),
+            WindowEvent::CursorLeft { .. } => self.inject(InputEvent::CursorLeft),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) -> bool {
+        match event {
+            DeviceEvent::MouseMotion { delta } => {
+                self.inject(InputEvent::MouseMotion([delta.0 as f32, delta.1 as f32]));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Aplica um evento como se tivesse vindo do sistema (testes, replays, entrada virtual)
+    pub fn inject(&mut self, event: InputEvent) {
+        self.events.push(event);
+        match event {
+            InputEvent::KeyPressed(key) => self.keys.press(key),
+            InputEvent::KeyReleased(key) => self.keys.release(key),
+            InputEvent::MousePressed(button) => self.mouse_buttons.press(button),
+            InputEvent::MouseReleased(button) => self.mouse_buttons.release(button),
+            InputEvent::CursorMoved(position) => {
+                let position = Vec2::from(position);
+                if self.cursor_inside {
+                    self.mouse_delta += position - self.mouse_position;
+                }
+                self.mouse_position = position;
+                self.cursor_inside = true;
+            }
+            InputEvent::CursorLeft => self.cursor_inside = false,
+            InputEvent::MouseMotion(delta) => self.mouse_motion += Vec2::from(delta),
+            InputEvent::MouseWheel(delta) => self.mouse_wheel += Vec2::from(delta),
+            InputEvent::Text(c) => {
+                // Backspace, enter e afins chegam como teclas, não como texto
+                if !c.is_control() {
+                    self.text.push(c);
+                }
+            }
+            InputEvent::FocusLost => {
+                self.keys.release_all();
+                self.mouse_buttons.release_all();
+            }
+            InputEvent::GamepadConnected(id) => {
+                self.gamepads.entry(id).or_default();
+            }
+            InputEvent::GamepadDisconnected(id) => {
+                self.gamepads.remove(&id);
+            }
+            InputEvent::GamepadPressed(id, button) => self.gamepads.entry(id).or_default().buttons.press(button),
+            InputEvent::GamepadReleased(id, button) => self.gamepads.entry(id).or_default().buttons.release(button),
+            InputEvent::GamepadAxisChanged(id, axis, value) => {
+                self.gamepads.entry(id).or_default().set_axis(axis, value);
+            }
+        }
+    }
+
+    // Fecha o frame: zera os `just_*`, os deltas do mouse, a roda e o texto digitado
+    pub fn end_frame(&mut self) {
+        self.keys.clear_frame();
+        self.mouse_buttons.clear_frame();
+        self.mouse_delta = Vec2::ZERO;
+        self.mouse_motion = Vec2::ZERO;
+        self.mouse_wheel = Vec2::ZERO;
+        self.text.clear();
+        self.events.clear();
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.buttons.clear_frame();
+        }
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    pub fn keys(&self) -> &ButtonInput<Key> {
+        &self.keys
+    }
+
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.keys.is_pressed(key)
+    }
+
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.keys.just_pressed(key)
+    }
+
+    pub fn just_released(&self, key: Key) -> bool {
+        self.keys.just_released(key)
+    }
+
+    pub fn mouse_buttons(&self) -> &ButtonInput<MouseButton> {
+        &self.mouse_buttons
+    }
+
+    pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.is_pressed(button)
+    }
+
+    pub fn mouse_just_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_pressed(button)
+    }
+
+    pub fn mouse_just_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_released(button)
+    }
+
+    // Posição do cursor na janela (pixels físicos). Para o mundo, use `Camera2D::screen_to_world`.
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse_position
+    }
+
+    // Quanto o cursor andou neste frame, em pixels da janela
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+
+    // Movimento bruto do mouse neste frame (sem aceleração do sistema, sem limite da janela)
+    pub fn mouse_motion(&self) -> Vec2 {
+        self.mouse_motion
+    }
+
+    pub fn mouse_wheel(&self) -> Vec2 {
+        self.mouse_wheel
+    }
+
+    pub fn cursor_inside(&self) -> bool {
+        self.cursor_inside
+    }
+
+    // Texto digitado neste frame (já com layout de teclado, acentos e maiúsculas)
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.keys().copied()
+    }
+
+    pub fn gamepad(&self, id: GamepadId) -> Option<&GamepadState> {
+        self.gamepads.get(&id)
+    }
+
+    // Os eventos não trazem o nome do dispositivo; o backend informa depois da conexão
+    pub fn set_gamepad_name(&mut self, id: GamepadId, name: impl Into<String>) {
+        if let Some(gamepad) = self.gamepads.get_mut(&id) {
+            gamepad.name = name.into();
+        }
+    }
+
+    pub fn gamepad_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad(id).is_some_and(|gamepad| gamepad.buttons.is_pressed(button))
+    }
+
+    pub fn gamepad_just_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad(id).is_some_and(|gamepad| gamepad.buttons.just_pressed(button))
+    }
+
+    pub fn gamepad_just_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad(id).is_some_and(|gamepad| gamepad.buttons.just_released(button))
+    }
+
+    // Valor do eixo (-1..1, gatilhos 0..1); 0 se o gamepad não estiver conectado
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepad(id).map_or(0.0, |gamepad| gamepad.axis(axis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_just_pressed_for_one_frame() {
+        let mut input = Input::new();
+        input.inject(InputEvent::KeyPressed(Key::A));
+        assert!(input.is_pressed(Key::A) && input.just_pressed(Key::A) && !input.just_released(Key::A));
+
+        input.end_frame();
+        assert!(input.is_pressed(Key::A) && !input.just_pressed(Key::A));
+
+        // Repetição do sistema com a tecla já pressionada não é um novo `just_pressed`
+        input.inject(InputEvent::KeyPressed(Key::A));
+        assert!(!input.just_pressed(Key::A));
+
+        input.inject(InputEvent::KeyReleased(Key::A));
+        assert!(!input.is_pressed(Key::A) && input.just_released(Key::A));
+        input.end_frame();
+        assert!(!input.just_released(Key::A));
+    }
+
+    #[test]
+    fn press_and_release_in_the_same_frame_are_both_seen() {
+        let mut input = Input::new();
+        input.inject(InputEvent::MousePressed(MouseButton::Left));
+        input.inject(InputEvent::MouseReleased(MouseButton::Left));
+        assert!(!input.is_mouse_pressed(MouseButton::Left));
+        assert!(input.mouse_just_pressed(MouseButton::Left) && input.mouse_just_released(MouseButton::Left));
+        input.end_frame();
+        assert!(!input.mouse_just_pressed(MouseButton::Left) && !input.mouse_just_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn mouse_deltas_and_text_reset_each_frame() {
+        let mut input = Input::new();
+        input.inject(InputEvent::CursorMoved([10.0, 10.0]));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);  // Primeira posição não gera delta
+        input.inject(InputEvent::CursorMoved([13.0, 6.0]));
+        input.inject(InputEvent::MouseWheel([0.0, 2.0]));
+        input.inject(InputEvent::Text('o'));
+        input.inject(InputEvent::Text('\r'));
+        assert_eq!(input.mouse_delta(), Vec2::new(3.0, -4.0));
+        assert_eq!(input.mouse_wheel(), Vec2::new(0.0, 2.0));
+        assert_eq!(input.text(), "o");
+
+        input.end_frame();
+        assert_eq!(input.mouse_position(), Vec2::new(13.0, 6.0));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.mouse_wheel(), Vec2::ZERO);
+        assert_eq!(input.text(), "");
+        assert!(input.events().is_empty());
+
+        input.inject(InputEvent::CursorLeft);
+        input.inject(InputEvent::CursorMoved([50.0, 50.0]));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn focus_loss_releases_everything() {
+        let mut input = Input::new();
+        input.inject(InputEvent::KeyPressed(Key::Space));
+        input.inject(InputEvent::MousePressed(MouseButton::Right));
+        input.end_frame();
+        input.inject(InputEvent::FocusLost);
+        assert!(!input.is_pressed(Key::Space) && input.just_released(Key::Space));
+        assert!(!input.is_mouse_pressed(MouseButton::Right) && input.mouse_just_released(MouseButton::Right));
+    }
+
+    #[test]
+    fn gamepad_buttons_and_axes() {
+        let pad = GamepadId(1);
+        let mut input = Input::new();
+        input.inject(InputEvent::GamepadConnected(pad));
+        input.inject(InputEvent::GamepadPressed(pad, GamepadButton::South));
+        input.inject(InputEvent::GamepadAxisChanged(pad, GamepadAxis::LeftStickX, 2.0));
+        assert_eq!(input.gamepads().collect::<Vec<_>>(), [pad]);
+        assert!(input.gamepad_pressed(pad, GamepadButton::South) && input.gamepad_just_pressed(pad, GamepadButton::South));
+        assert_eq!(input.gamepad_axis(pad, GamepadAxis::LeftStickX), 1.0);
+
+        input.end_frame();
+        assert!(input.gamepad_pressed(pad, GamepadButton::South) && !input.gamepad_just_pressed(pad, GamepadButton::South));
+        input.inject(InputEvent::GamepadReleased(pad, GamepadButton::South));
+        assert!(input.gamepad_just_released(pad, GamepadButton::South));
+
+        input.end_frame();
+        input.inject(InputEvent::GamepadDisconnected(pad));
+        assert!(input.gamepad(pad).is_none());
+        assert!(!input.gamepad_pressed(pad, GamepadButton::South));
+        assert_eq!(input.gamepad_axis(pad, GamepadAxis::LeftStickX), 0.0);
+    }
+}
@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::is_ron;
+
+use super::{Input, InputEvent};
+
+// Os eventos de entrada de um frame, na ordem em que chegaram, e o delta real do frame
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<InputEvent>,
+    // Novo tamanho da área de desenho, se a janela foi redimensionada antes deste frame
+    // (inclui mudanças de escala do monitor)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resize: Option<(u32, u32)>,
+}
+
+// Sessão gravada: o suficiente para repetir a simulação frame a frame sem janela.
+// A repetição só é fiel se o jogo for determinístico a partir da entrada e do tempo
+// (sementes de números aleatórios fixas, lógica lendo `ctx.input`/`ctx.actions`, não `on_event`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub fixed_timestep: f32,
+    pub size: (u32, u32),  // Tamanho da área de desenho quando a gravação começou
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.delta).sum()
+    }
+
+    // Formato escolhido pela extensão: .ron ou JSON
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Erro ao ler a gravação {}: {}", path.display(), e))?;
+        if is_ron(path) {
+            ron::from_str(&text).map_err(|e| anyhow::anyhow!("Gravação RON inválida {}: {}", path.display(), e))
+        } else {
+            serde_json::from_str(&text).map_err(|e| anyhow::anyhow!("Gravação JSON inválida {}: {}", path.display(), e))
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = if is_ron(path) {
+            ron::to_string(self)?
+        } else {
+            serde_json::to_string(self)?
+        };
+        std::fs::write(path, text).map_err(|e| anyhow::anyhow!("Erro ao salvar a gravação {}: {}", path.display(), e))
+    }
+}
+
+// Grava os eventos do `Input` de cada frame. Chamar `record_frame` antes do `Input::end_frame`.
+#[derive(Clone, Debug)]
+pub struct InputRecorder {
+    recording: Recording,
+    size: (u32, u32),  // Tamanho no último frame gravado
+}
+
+impl InputRecorder {
+    pub fn new(fixed_timestep: f32, size: (u32, u32)) -> Self {
+        Self {
+            recording: Recording {
+                fixed_timestep,
+                size,
+                frames: Vec::new(),
+            },
+            size,
+        }
+    }
+
+    // `size` é o tamanho atual da área de desenho; só é gravado quando muda
+    pub fn record_frame(&mut self, delta: f32, input: &Input, size: (u32, u32)) {
+        let resize = (size != self.size).then_some(size);
+        self.size = size;
+        self.recording.frames.push(RecordedFrame {
+            delta,
+            events: input.events().to_vec(),
+            resize,
+        });
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.recording.frames.len()
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+// Reinjeta uma gravação no `Input`, um frame por vez
+#[derive(Clone, Debug)]
+pub struct InputReplayer {
+    recording: Recording,
+    next: usize,
+}
+
+impl InputReplayer {
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, next: 0 }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    // Índice do próximo frame a ser reproduzido
+    pub fn frame_index(&self) -> usize {
+        self.next
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.frames.len()
+    }
+
+    // Aplica os eventos do próximo frame e o devolve (delta e redimensionamento ficam com quem
+    // chama); `None` no fim da gravação
+    pub fn next_frame(&mut self, input: &mut Input) -> Option<&RecordedFrame> {
+        let frame = self.recording.frames.get(self.next)?;
+        self.next += 1;
+        for event in &frame.events {
+            input.inject(*event);
+        }
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{GamepadButton, GamepadId, Key, MouseButton};
+
+    // Três frames de entrada gravados a partir de eventos injetados
+    pub(crate) fn sample_recording() -> Recording {
+        let frames: [(f32, Vec<InputEvent>); 3] = [
+            (0.016, vec![InputEvent::KeyPressed(Key::W), InputEvent::CursorMoved([10.0, 20.0])]),
+            (0.020, vec![InputEvent::MousePressed(MouseButton::Left), InputEvent::CursorMoved([15.0, 18.0]), InputEvent::Text('a')]),
+            (0.017, vec![
+                InputEvent::KeyReleased(Key::W),
+                InputEvent::GamepadPressed(GamepadId(0), GamepadButton::South),
+                InputEvent::MouseWheel([0.0, 1.0]),
+            ]),
+        ];
+        let mut input = Input::new();
+        let mut recorder = InputRecorder::new(1.0 / 60.0, (64, 64));
+        for (i, (delta, events)) in frames.into_iter().enumerate() {
+            for event in events {
+                input.inject(event);
+            }
+            let size = if i == 2 { (80, 60) } else { (64, 64) };
+            recorder.record_frame(delta, &input, size);
+            input.end_frame();
+        }
+        recorder.finish()
+    }
+
+    #[test]
+    fn recorder_keeps_events_in_order_and_only_records_size_changes() {
+        let recording = sample_recording();
+        assert_eq!(recording.frames.len(), 3);
+        assert_eq!(recording.frames[0].events, [InputEvent::KeyPressed(Key::W), InputEvent::CursorMoved([10.0, 20.0])]);
+        assert_eq!(recording.frames.iter().map(|frame| frame.resize).collect::<Vec<_>>(), [None, None, Some((80, 60))]);
+        assert!((recording.duration() - 0.053).abs() < 1e-6);
+    }
+
+    #[test]
+    fn recording_round_trips_through_ron_and_json() {
+        let recording = sample_recording();
+        let dir = std::env::temp_dir();
+        for extension in ["ron", "json"] {
+            let path = dir.join(format!("base-replay-test-{}.{}", std::process::id(), extension));
+            recording.save(&path).unwrap();
+            let loaded = Recording::load(&path);
+            let _ = std::fs::remove_file(&path);
+            assert_eq!(loaded.unwrap(), recording, "{}", extension);
+        }
+    }
+
+    #[test]
+    fn replayer_reinjects_each_frame() {
+        let recording = sample_recording();
+        let mut replayer = InputReplayer::new(recording.clone());
+        let mut input = Input::new();
+
+        let frame = replayer.next_frame(&mut input).unwrap();
+        assert_eq!(frame.delta, 0.016);
+        assert!(input.just_pressed(Key::W));
+        assert_eq!(input.events(), recording.frames[0].events.as_slice());
+        input.end_frame();
+
+        replayer.next_frame(&mut input).unwrap();
+        assert!(input.is_pressed(Key::W) && !input.just_pressed(Key::W));
+        assert_eq!(input.text(), "a");
+        input.end_frame();
+
+        assert_eq!(replayer.next_frame(&mut input).unwrap().resize, Some((80, 60)));
+        assert!(input.just_released(Key::W));
+        assert!(replayer.is_finished());
+        assert!(replayer.next_frame(&mut input).is_none());
+    }
+}
@@ -1 +1,14 @@
-pub mod graphics;
\ No newline at end of file
+pub mod app;
+pub mod asset;
+pub mod ecs;
+pub mod graphics;
+pub mod input;
+pub mod time;
+
+pub use app::{App, AppContext, AppRunner};
+pub use asset::AssetServer;
+pub use input::Input;
+pub use time::Time;
+
+// Reexportado para que os jogos usem os mesmos tipos de vetores e matrizes da base
+pub use glam;
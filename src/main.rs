@@ -1,51 +1,52 @@
-use winit::{
-    event_loop::{ControlFlow,EventLoop},
-    window::WindowBuilder,
-};
-
 use anyhow::Result; // Para lidar com erros
-use graphics::render::Render;
-mod graphics;
+use base::asset::Handle;
+use base::graphics::sprite::{Sprite, SpriteBatch};
+use base::graphics::texture::Texture;
+use base::input::Recording;
+use base::{App, AppContext, AppRunner};
 
+#[derive(Default)]
+struct Demo {
+    texture: Option<Handle<Texture>>,
+}
 
-fn main() -> Result<()> {
-    // Criar o event loop e a janela
-    let event_loop = EventLoop::new();
-    let window = WindowBuilder::new().build(&event_loop)?;
+impl App for Demo {
+    fn init(&mut self, ctx: &mut AppContext) -> Result<()> {
+        // Carregar a textura (em segundo plano; aparece quando estiver pronta)
+        self.texture = Some(ctx.assets.load("images/razorfuture.jpeg"));
+        Ok(())
+    }
 
-    // Rodar a função assíncrona principal
-    pollster::block_on(run(event_loop, window))
+    fn render(&mut self, ctx: &mut AppContext, batch: &mut SpriteBatch) {
+        // Textura centrada na câmera, ocupando metade da área visível
+        if let Some(texture) = self.texture.and_then(|texture| ctx.assets.texture(texture)) {
+            let camera = ctx.render.camera();
+            batch.push(
+                texture,
+                Sprite {
+                    position: camera.position.into(),
+                    scale: (camera.visible_size() * 0.5).into(),
+                    ..Default::default()
+                },
+            );
+        }
+    }
 }
 
-async fn run(event_loop: EventLoop<()>, window: winit::window::Window) -> Result<()> {
-    // Inicializar o renderizador
-    let mut render = Render::new(&window).await?;
-    
-    // Carregar a textura
-    let (_, bind_group) = render.load_texture("src/assets/images/razorfuture.jpeg")?;
-    
-    // Iniciar o loop de eventos
-    event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Poll;  // Define para verificar os eventos constantemente
-    
-        match event {
-            winit::event::Event::WindowEvent { event, .. } => match event {
-                winit::event::WindowEvent::CloseRequested => {
-                    *control_flow = ControlFlow::Exit;  // Fechar a janela corretamente
-                }
-                winit::event::WindowEvent::Resized(physical_size) => {
-                    // Redimensionar a janela
-                    render.resize(physical_size);
-                }
-                _ => {}
-            },
-            winit::event::Event::RedrawRequested(_) => {
-                if let Err(e) = render.render(&bind_group) {
-                    eprintln!("Render error: {:?}", e);
-                }
-            }
-            _ => {}
+// `--record <arquivo>` grava a entrada da sessão; `--replay <arquivo>` a reproduz sem janela
+fn main() -> Result<()> {
+    let runner = AppRunner::new(Demo::default()).title("Razor");
+    let mut args = std::env::args().skip(1);
+    match (args.next().as_deref(), args.next()) {
+        (None, _) => runner.run(),
+        (Some("--record"), Some(path)) => runner.record_to(path).run(),
+        (Some("--replay"), Some(path)) => {
+            let recording = Recording::load(&path)?;
+            let frames = recording.frames.len();
+            runner.run_replay(recording)?;
+            println!("Replayed {} frames from {}", frames, path);
+            Ok(())
         }
-    });
-    
-}    
+        _ => Err(anyhow::anyhow!("Usage: base [--record <file> | --replay <file>]")),
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Instant;
+
+// Maior intervalo aceito entre dois frames (segundos). Evita a "espiral da morte"
+// do passo fixo depois de uma travada longa (janela arrastada, breakpoint...)
+const DEFAULT_MAX_FRAME_TIME: f32 = 0.25;
+
+// Relógio do jogo: tempo do frame, tempo total, contagem de frames e o acumulador do passo fixo.
+//
+// O tempo "escalado" (delta, elapsed, passo fixo) respeita `time_scale` e a pausa;
+// o tempo "real" (raw_*) não, e serve para UI e menus que continuam durante a pausa.
+//
+//     time.tick();
+//     while time.expend_fixed_step() {
+//         physics.step(time.fixed_timestep());
+//     }
+//     let alpha = time.alpha();  // Interpolar entre o estado anterior e o atual ao desenhar
+#[derive(Clone, Debug)]
+pub struct Time {
+    delta: f32,
+    raw_delta: f32,
+    elapsed: f64,
+    raw_elapsed: f64,
+    frame_count: u64,
+    fixed_step_count: u64,
+    time_scale: f32,
+    paused: bool,
+    fixed_timestep: f32,
+    accumulator: f32,      // Tempo escalado ainda não consumido por passos fixos
+    max_frame_time: f32,
+    last_tick: Option<Instant>,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new(1.0 / 60.0)
+    }
+}
+
+impl Time {
+    pub fn new(fixed_timestep: f32) -> Self {
+        Self {
+            delta: 0.0,
+            raw_delta: 0.0,
+            elapsed: 0.0,
+            raw_elapsed: 0.0,
+            frame_count: 0,
+            fixed_step_count: 0,
+            time_scale: 1.0,
+            paused: false,
+            fixed_timestep: fixed_timestep.max(f32::EPSILON),
+            accumulator: 0.0,
+            max_frame_time: DEFAULT_MAX_FRAME_TIME,
+            last_tick: None,
+        }
+    }
+
+    // Começa um novo frame medindo o tempo desde o `tick` anterior (o primeiro frame tem delta zero)
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let raw_delta = self.last_tick.map_or(0.0, |last| (now - last).as_secs_f32());
+        self.last_tick = Some(now);
+        self.advance(raw_delta);
+    }
+
+    // Começa um novo frame com um delta conhecido, sem olhar o relógio
+    // (modo headless, testes e replays determinísticos)
+    pub fn advance(&mut self, raw_delta: f32) {
+        let raw_delta = raw_delta.clamp(0.0, self.max_frame_time);
+        self.raw_delta = raw_delta;
+        self.raw_elapsed += raw_delta as f64;
+        self.delta = if self.paused { 0.0 } else { raw_delta * self.time_scale };
+        self.elapsed += self.delta as f64;
+        self.accumulator += self.delta;
+        self.frame_count += 1;
+    }
+
+    // Consome um passo fixo do acumulador; chamar em laço até retornar `false`
+    pub fn expend_fixed_step(&mut self) -> bool {
+        if self.accumulator < self.fixed_timestep {
+            return false;
+        }
+        self.accumulator -= self.fixed_timestep;
+        self.fixed_step_count += 1;
+        true
+    }
+
+    // Fração do próximo passo fixo já passada (0..1), para interpolar a renderização
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.fixed_timestep).clamp(0.0, 1.0)
+    }
+
+    // Delta do frame em segundos, com escala e pausa aplicadas
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn raw_delta(&self) -> f32 {
+        self.raw_delta
+    }
+
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    pub fn raw_elapsed(&self) -> f64 {
+        self.raw_elapsed
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn fixed_step_count(&self) -> u64 {
+        self.fixed_step_count
+    }
+
+    pub fn fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    pub fn set_fixed_timestep(&mut self, seconds: f32) {
+        self.fixed_timestep = seconds.max(f32::EPSILON);
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    // 0.5 = câmera lenta, 2.0 = dobro da velocidade; o passo fixo continua do mesmo tamanho,
+    // só acontece com mais ou menos frequência
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+    }
+
+    pub fn max_frame_time(&self) -> f32 {
+        self.max_frame_time
+    }
+
+    pub fn set_max_frame_time(&mut self, seconds: f32) {
+        self.max_frame_time = seconds.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(time: &mut Time) -> u32 {
+        let mut count = 0;
+        while time.expend_fixed_step() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn fixed_steps_consume_the_accumulated_time() {
+        let mut time = Time::new(0.01);
+        time.advance(0.035);
+        assert_eq!(steps(&mut time), 3);
+        assert!((time.alpha() - 0.5).abs() < 1e-4);
+
+        // O resto acumula para o próximo frame
+        time.advance(0.006);
+        assert_eq!(steps(&mut time), 1);
+        assert!((time.alpha() - 0.1).abs() < 1e-4);
+        assert_eq!(time.fixed_step_count(), 4);
+        assert_eq!(time.frame_count(), 2);
+
+        time.advance(0.001);
+        assert_eq!(steps(&mut time), 0);
+    }
+
+    #[test]
+    fn huge_deltas_are_clamped() {
+        // Passo exato em binário, para a contagem não depender de arredondamento
+        let mut time = Time::new(1.0 / 64.0);
+        time.advance(10.0);
+        assert_eq!(time.delta(), DEFAULT_MAX_FRAME_TIME);
+        assert_eq!(time.raw_delta(), DEFAULT_MAX_FRAME_TIME);
+        assert_eq!(steps(&mut time), 16);
+
+        time.set_max_frame_time(0.125);
+        time.advance(1.0);
+        assert_eq!(steps(&mut time), 8);
+
+        time.advance(-1.0);
+        assert_eq!(time.delta(), 0.0);
+    }
+
+    #[test]
+    fn pause_and_scale_affect_only_scaled_time() {
+        let mut time = Time::new(0.01);
+        time.set_time_scale(0.5);
+        time.advance(0.04);
+        assert_eq!(time.delta(), 0.02);
+        assert_eq!(time.raw_delta(), 0.04);
+        assert_eq!(steps(&mut time), 2);
+
+        time.pause();
+        time.advance(0.04);
+        assert_eq!(time.delta(), 0.0);
+        assert_eq!(steps(&mut time), 0);
+        assert!((time.elapsed() - 0.02).abs() < 1e-6);
+        assert!((time.raw_elapsed() - 0.08).abs() < 1e-6);
+
+        time.toggle_pause();
+        time.set_time_scale(2.0);
+        time.advance(0.02);
+        assert_eq!(time.delta(), 0.04);
+        assert_eq!(steps(&mut time), 4);
+
+        time.set_time_scale(-1.0);
+        assert_eq!(time.time_scale(), 0.0);
+    }
+}